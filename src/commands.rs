@@ -1,200 +1,574 @@
-use crate::git::run_git_command;
+use crate::error::CrescaError;
+use crate::git::{
+    branch_exists, create_empty_root_commit, empty_tree, get_branch_review_info, get_config,
+    get_config_all, get_current_branch, get_default_branch, get_file_stats, get_merge_base,
+    have_unrelated_histories, is_clean, is_fixed_revision, is_merge_commit, remote_exists,
+    remove_review_branch_info, rev_list, rev_parse, review_branch_name, run_git_command,
+    run_git_command_with_timeout, run_interactive_git_command, run_mutating_git_command,
+    run_streaming_git_command, set_config, set_config_all, set_git_work_dir,
+    set_review_branch_info, unset_config, DiffStats, FileStat, GitRunner,
+};
+use crate::messages::{fill, fill_plural, msg, Id};
+use crate::progress::Spinner;
 use colored::Colorize;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::ops::Not;
-use std::process::exit;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Default commit message for approved changes
+const DEFAULT_APPROVE_MESSAGE: &str = "Approve reviewed changes";
+
+/// Get the ref the head of a GitHub pull request is fetched into
+///
+/// # Arguments
+///
+/// * `number` - The pull request number.
+fn pull_request_ref(number: u64) -> String {
+    format!("refs/cresca/pr/{}", number)
+}
+
+/// Options for preparing the review branch
+#[derive(Default)]
+pub struct ReviewOptions<'a> {
+    /// Optional commit hash to skip to (auto-approve earlier commits).
+    pub skip_to: Option<&'a str>,
+    /// Optional commit hash to stop at (exclude later commits from review).
+    pub stop_at: Option<&'a str>,
+    /// The remote to pull the branches from.
+    pub remote: &'a str,
+    /// Whether to skip pulling the branches and use the local branch tips.
+    pub no_pull: bool,
+    /// How long to let each pull or fetch from the remote run before giving up. Waits forever if `None`.
+    pub timeout: Option<Duration>,
+    /// How many times to retry a failed pull or fetch from the remote.
+    pub retries: u32,
+    /// Whether to show a spinner on stderr while pulling and merging. Should be `false` unless stderr is a
+    /// terminal. Never shown in dry runs.
+    pub progress: bool,
+    /// Optional commit message for the auto-approved commits. Defaults to a summary of the auto-approved commits.
+    pub auto_approve_message: Option<&'a str>,
+    /// Whether to print the git commands that modify the repository instead of running them.
+    pub dry_run: bool,
+    /// The prefix of review branch names.
+    pub prefix: &'a str,
+    /// Strategy options passed to `git merge -X`. Defaults to `theirs` if empty.
+    pub strategy_options: &'a [String],
+    /// Whether to stop on merge conflicts instead of resolving them with the default `-X theirs`.
+    pub detect_conflicts: bool,
+    /// Whether to ignore carriage returns at the end of lines in the merges and the remaining diff,
+    /// so CRLF normalization differences are not reviewed.
+    pub ignore_eol: bool,
+    /// Whether to review the commits one by one instead of squash merging them. Each commit is
+    /// cherry-picked without committing, and `review_next_commit` moves on to the next one.
+    pub no_squash: bool,
+    /// Optional GitHub pull request number whose head is fetched into `refs/cresca/pr/<number>` and reviewed
+    /// instead of the development branch, which is the head branch of the PR and only names the review branch.
+    pub pull_request: Option<u64>,
+    /// Whether the branches are the base and head of an explicit commit range. Nothing is switched or
+    /// pulled, and the review branch is created off the base instead of the merge base.
+    pub range: bool,
+    /// Whether to sign the auto-approve commit.
+    pub sign: bool,
+    /// Whether to bypass the pre-commit and commit-msg hooks for the auto-approve commit.
+    pub no_verify: bool,
+    /// The author of the auto-approve commit in the `Name <email>` format. Defaults to the git identity.
+    pub author: Option<&'a str>,
+    /// Whether to recreate an existing review branch from scratch, discarding prior approvals.
+    pub force: bool,
+    /// Whether to review branches with unrelated histories from an empty root commit instead of failing.
+    pub allow_unrelated: bool,
+    /// Globs of files to approve automatically right after the squash merge, e.g. lockfiles.
+    pub auto_approve: &'a [String],
+    /// Globs of files to leave out of the review. They are reverted to the review branch right after
+    /// the squash merge, and neither shown nor approved.
+    pub exclude: &'a [String],
+    /// Paths relative to the repository root to limit the review to. Changes outside of them are
+    /// discarded from the working tree and left out of the status. All files are reviewed if empty.
+    pub paths: &'a [String],
+    /// Optional path of a git worktree to prepare the review branch in. The current checkout stays on its
+    /// branch, and the branches are fetched instead of switched to and pulled. Once the worktree is
+    /// created, git runs in it (see `set_git_work_dir`), so later git commands also target the worktree.
+    pub worktree: Option<&'a Path>,
+}
+
+/// Whether an interrupt was requested with `request_interrupt`
+static INTERRUPT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Ask `prepare_review_branch` to stop before its next step, e.g. from a Ctrl-C handler
+///
+/// Only sets a flag, so it can be called from any thread. The git command running at the time is not
+/// killed, though a terminal interrupts it along with cresca.
+pub fn request_interrupt() {
+    INTERRUPT_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Check whether an interrupt was requested with `request_interrupt`
+pub fn interrupt_requested() -> bool {
+    INTERRUPT_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Stop with `CrescaError::Interrupted` if an interrupt was requested
+fn check_interrupt() -> Result<(), CrescaError> {
+    if interrupt_requested() {
+        return Err(CrescaError::Interrupted);
+    }
+    Ok(())
+}
 
 /// Prepare the review branch using Squash Merge approach.
 ///
 /// # Arguments
 ///
+/// * `git` - The runner of the git commands.
 /// * `to_branch` - The branch where the PR is planned to be merged into.
 /// * `from_branch` - The development branch to be reviewed.
-/// * `skip_to` - Optional commit hash to skip to (auto-approve earlier commits).
-/// * `stop_at` - Optional commit hash to stop at (exclude later commits from review).
-/// * `verbose` - Whether to print the git command and its output.
+/// * `options` - Options for preparing the review branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(true)` - If the review branch was prepared
+/// * `Ok(false)` - If `from_branch` is already merged into `to_branch`, so no review branch was prepared
+/// * `Err(CrescaError::Interrupted)` - If `request_interrupt` was called, checked between the steps
+/// * `Err(CrescaError)` - If preparing the review branch failed
 pub fn prepare_review_branch(
+    git: &impl GitRunner,
     to_branch: &str,
     from_branch: &str,
-    skip_to: Option<&str>,
-    stop_at: Option<&str>,
-    verbose: bool,
-) {
-    let review_branch = format!("review-{}-{}", to_branch, from_branch);
-
-    // Fetch and update both branches
-    run_git_command(
-        &format!("switch to {} branch", from_branch),
-        &["switch", from_branch],
-        false,
-        verbose,
-    );
-    run_git_command(
-        &format!("pull {} branch", from_branch),
-        &["pull", "origin", from_branch],
-        false,
-        verbose,
-    );
-    run_git_command(
-        &format!("switch to {} branch", to_branch),
-        &["switch", to_branch],
-        false,
-        verbose,
-    );
-    run_git_command(
-        &format!("pull {} branch", to_branch),
-        &["pull", "origin", to_branch],
-        false,
-        verbose,
-    );
+    options: &ReviewOptions,
+    verbose: u8,
+) -> Result<bool, CrescaError> {
+    let ReviewOptions {
+        skip_to,
+        stop_at,
+        remote,
+        no_pull,
+        timeout,
+        retries,
+        progress,
+        auto_approve_message,
+        dry_run,
+        prefix,
+        strategy_options,
+        detect_conflicts,
+        ignore_eol,
+        no_squash,
+        pull_request,
+        range,
+        sign,
+        no_verify,
+        author,
+        force,
+        allow_unrelated,
+        auto_approve,
+        exclude,
+        paths,
+        worktree,
+    } = *options;
+    let review_branch = review_branch_name(prefix, to_branch, from_branch);
+    // The head of a PR is fetched into a ref of cresca, so a local branch of the same name is left alone
+    let pull_request_head = pull_request.map(|_| from_branch);
+    let pull_request_ref = pull_request.map(pull_request_ref);
+    let from_branch = pull_request_ref.as_deref().unwrap_or(from_branch);
 
-    // Get merge-base
-    let merge_base_output = run_git_command(
-        "get merge base",
-        &["merge-base", to_branch, from_branch],
-        false,
-        verbose,
-    );
-    let merge_base = String::from_utf8_lossy(&merge_base_output.stdout)
-        .trim()
-        .to_string();
+    if to_branch == from_branch {
+        return Err(CrescaError::SameBranch(to_branch.to_string()));
+    }
+    build_glob_set(auto_approve)?;
+    build_glob_set(exclude)?;
+    if let Some(author) = author {
+        validate_author(author)?;
+    }
+    let network = NetworkFlags { timeout, retries };
+    let progress = progress && !dry_run;
+    let strategy_options = &merge_strategy_options(strategy_options, detect_conflicts, ignore_eol);
 
-    // Get valid commit range (merge_base..from_branch)
-    let valid_commits = run_git_command(
-        "get valid commit range",
-        &["rev-list", &format!("{}..{}", merge_base, from_branch)],
-        false,
-        verbose,
-    );
-    let valid_list = String::from_utf8_lossy(&valid_commits.stdout);
-    let valid_hashes: Vec<&str> = valid_list.lines().collect();
+    // Fetch and update both branches
+    if !no_pull && !range {
+        if !remote_exists(git, remote, verbose)? {
+            return Err(CrescaError::RemoteNotFound(remote.to_string()));
+        }
 
-    // Validate skip_to if provided
-    if let Some(hash) = skip_to {
-        let is_valid = valid_hashes.iter().any(|line| line.starts_with(hash));
-        if !is_valid {
-            eprintln!(
-                "{}: Commit {} is not in the range {}..{}",
-                "error".red().bold(),
-                hash,
+        // Tags and commits cannot be switched to or pulled, so they are used as they are
+        if pull_request.is_none() && !is_fixed_revision(git, from_branch, remote, verbose) {
+            let _spinner = Spinner::start(fill(Id::Fetching, &[&from_branch]), progress);
+            update_branch(
+                git,
+                from_branch,
+                remote,
+                worktree.is_some(),
+                network,
+                dry_run,
+                verbose,
+            )?;
+            check_interrupt()?;
+        }
+        if !is_fixed_revision(git, to_branch, remote, verbose) {
+            let _spinner = Spinner::start(fill(Id::Fetching, &[&to_branch]), progress);
+            update_branch(
+                git,
                 to_branch,
-                from_branch
-            );
-            exit(1);
+                remote,
+                worktree.is_some(),
+                network,
+                dry_run,
+                verbose,
+            )?;
+            check_interrupt()?;
+        }
+        // The head of a PR from a fork is only available as `pull/<number>/head`
+        if let Some(number) = pull_request {
+            let _spinner = Spinner::start(fill(Id::FetchingPullRequest, &[&number]), progress);
+            run_network_git_command(
+                git,
+                &format!("fetch pull request #{}", number),
+                &[
+                    "fetch",
+                    "--quiet",
+                    remote,
+                    &format!("+pull/{}/head:{}", number, from_branch),
+                ],
+                network,
+                dry_run,
+                verbose,
+            )?;
+            check_interrupt()?;
+        }
+    }
+
+    // Branches pointing to the same commit have nothing to review
+    if let (Ok(to_commit), Ok(from_commit)) = (
+        rev_parse(git, to_branch, verbose),
+        rev_parse(git, from_branch, verbose),
+    ) {
+        if to_commit == from_commit {
+            return Err(CrescaError::SameCommit {
+                to_branch: to_branch.to_string(),
+                from_branch: from_branch.to_string(),
+            });
         }
     }
 
-    // Validate stop_at if provided
-    if let Some(hash) = stop_at {
-        // stop_at must be in the valid range
-        let is_valid = valid_hashes.iter().any(|line| line.starts_with(hash));
-        if !is_valid {
+    // A development branch already merged into the target branch has nothing to review
+    if !range
+        && run_git_command(
+            git,
+            "check whether the development branch is already merged",
+            &["merge-base", "--is-ancestor", from_branch, to_branch],
+            verbose,
+        )
+        .is_ok()
+    {
+        return Ok(false);
+    }
+
+    if range {
+        let is_descendant = run_git_command(
+            git,
+            "check whether the head of the range is a descendant of the base",
+            &["merge-base", "--is-ancestor", to_branch, from_branch],
+            verbose,
+        )
+        .is_ok();
+        if !is_descendant {
             eprintln!(
-                "{}: Commit {} is not in the range {}..{}",
-                "error".red().bold(),
-                hash,
-                to_branch,
-                from_branch
+                "{}: {}",
+                msg(Id::Warning).yellow().bold(),
+                fill(Id::NotDescendant, &[&from_branch, &to_branch])
             );
-            exit(1);
         }
+    }
 
-        // If skip_to is also specified, stop_at must be at or after skip_to
-        if let Some(skip_hash) = skip_to {
-            let skip_to_commits = run_git_command(
-                "get commits after skip_to",
-                &["rev-list", &format!("{}..{}", skip_hash, from_branch)],
-                false,
+    let base = if range {
+        Some(rev_parse(git, to_branch, verbose)?)
+    } else if allow_unrelated && have_unrelated_histories(git, to_branch, from_branch, verbose) {
+        // Review everything from an empty root commit, reusing the one of an existing review branch
+        let stored_merge_base =
+            get_config(git, &format!("cresca.{}.mergeBase", review_branch), verbose)
+                .filter(|_| !force && branch_exists(git, &review_branch, verbose));
+        Some(match stored_merge_base {
+            Some(merge_base) => merge_base,
+            None => create_empty_root_commit(
+                git,
+                &format!("Empty base of {} for unrelated histories", review_branch),
                 verbose,
-            );
-            let skip_to_list = String::from_utf8_lossy(&skip_to_commits.stdout);
-            let is_after_skip = skip_to_list.lines().any(|line| line.starts_with(hash))
-                || valid_hashes
-                    .iter()
-                    .any(|line| line.starts_with(hash) && line.starts_with(skip_hash));
-
-            // Check if stop_at equals skip_to (valid) or is after skip_to
-            let stop_at_equals_skip_to = valid_hashes
-                .iter()
-                .any(|line| line.starts_with(hash) && line.starts_with(skip_hash));
+            )?,
+        })
+    } else {
+        None
+    };
+    let commit_range =
+        resolve_commit_range(git, to_branch, from_branch, skip_to, stop_at, base, verbose)?;
+    let merge_base = commit_range.merge_base.as_str();
+    let skip_to = commit_range.skip_to.as_deref();
+    let stop_at = commit_range.stop_at.as_deref();
 
-            if !is_after_skip && !stop_at_equals_skip_to {
-                eprintln!(
-                    "{}: --stop-at ({}) must be at or after --skip-to ({})",
-                    "error".red().bold(),
-                    hash,
-                    skip_hash
-                );
-                exit(1);
+    // Prepare the review branch inside the worktree from here on
+    if let Some(path) = worktree {
+        if !path.exists() {
+            run_mutating_git_command(
+                git,
+                "create review worktree",
+                &[
+                    "worktree",
+                    "add",
+                    "--detach",
+                    &path.to_string_lossy(),
+                    merge_base,
+                ],
+                dry_run,
+                verbose,
+            )?;
+        }
+        if !dry_run {
+            // Only git runs in the worktree, so the current directory of the process is kept
+            let path = path.canonicalize().map_err(|e| CrescaError::Worktree {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+            set_git_work_dir(Some(&path));
+            if !is_clean(git, verbose)? {
+                return Err(CrescaError::DirtyWorkingTree);
             }
         }
     }
 
     // Check if review branch exists
-    let review_branch_exists = run_git_command(
-        "check existence of review branch",
-        &[
-            "show-ref",
-            "--verify",
-            &format!("refs/heads/{}", review_branch),
-        ],
-        true,
-        verbose,
-    )
-    .status
-    .success();
+    check_interrupt()?;
+    let merge_base_key = format!("cresca.{}.mergeBase", review_branch);
+    let review_branch_exists = branch_exists(git, &review_branch, verbose);
+    if review_branch_exists && !force {
+        // Continuing on top of commits that were rewritten away would mix the old and new history
+        let disappeared = disappeared_commits(git, &review_branch, from_branch, verbose);
+        if !disappeared.is_empty() {
+            return Err(CrescaError::ReviewedCommitsDisappeared {
+                branch: from_branch.to_string(),
+                commits: disappeared,
+            });
+        }
+
+        // A rebased development branch, or one that merged the target branch, moves the merge base
+        if let Some(stored_merge_base) = get_config(git, &merge_base_key, verbose) {
+            if stored_merge_base != merge_base {
+                eprintln!(
+                    "{}: {}",
+                    msg(Id::Warning).yellow().bold(),
+                    fill(
+                        Id::MergeBaseMoved,
+                        &[
+                            &to_branch,
+                            &from_branch,
+                            &&stored_merge_base[..stored_merge_base.len().min(7)],
+                            &&merge_base[..merge_base.len().min(7)],
+                            &"cresca review --force".green()
+                        ]
+                    )
+                );
+            }
+        }
 
-    if review_branch_exists {
         // Switch to existing review branch
-        run_git_command(
+        run_mutating_git_command(
+            git,
             "switch to review branch",
             &["switch", &review_branch],
-            false,
+            dry_run,
             verbose,
-        );
+        )?;
+    } else if review_branch_exists {
+        // Recreate the review branch from merge-base, dropping the info of the old one
+        if !dry_run {
+            remove_review_branch_info(git, &review_branch, verbose);
+        }
+        run_mutating_git_command(
+            git,
+            "recreate review branch from merge-base",
+            &["switch", "--force-create", &review_branch, merge_base],
+            dry_run,
+            verbose,
+        )?;
+        if !dry_run {
+            set_config(git, &merge_base_key, merge_base, verbose)?;
+        }
     } else {
         // Create review branch from merge-base
-        run_git_command(
+        run_mutating_git_command(
+            git,
             "create review branch from merge-base",
-            &["checkout", "-b", &review_branch, &merge_base],
-            false,
+            &["checkout", "-b", &review_branch, merge_base],
+            dry_run,
             verbose,
-        );
+        )?;
+        if !dry_run {
+            set_config(git, &merge_base_key, merge_base, verbose)?;
+        }
+    }
+
+    // Remember which branches this review branch belongs to
+    if !dry_run {
+        set_review_branch_info(
+            git,
+            &review_branch,
+            to_branch,
+            from_branch,
+            skip_to,
+            verbose,
+        )?;
+        let pull_request_head_key = format!("cresca.{}.pullRequestHead", review_branch);
+        match pull_request_head {
+            Some(head) => set_config(git, &pull_request_head_key, head, verbose)?,
+            None => unset_config(git, &pull_request_head_key, verbose),
+        }
+        set_config_all(
+            git,
+            &format!("cresca.{}.path", review_branch),
+            paths,
+            verbose,
+        )?;
+        set_config_all(
+            git,
+            &format!("cresca.{}.autoApprove", review_branch),
+            auto_approve,
+            verbose,
+        )?;
+        set_config_all(
+            git,
+            &format!("cresca.{}.exclude", review_branch),
+            exclude,
+            verbose,
+        )?;
+
+        if ignore_eol {
+            set_config(
+                git,
+                &format!("cresca.{}.ignoreEol", review_branch),
+                "true",
+                verbose,
+            )?;
+        }
+
+        // Remember how to apply the next commits of a no-squash review, or the remaining changes
+        // after `cresca continue`
+        if no_squash {
+            set_config(
+                git,
+                &format!("cresca.{}.noSquash", review_branch),
+                "true",
+                verbose,
+            )?;
+        }
+        set_config_all(
+            git,
+            &format!("cresca.{}.strategyOption", review_branch),
+            strategy_options,
+            verbose,
+        )?;
     }
+    let flags = CommitFlags {
+        sign,
+        no_verify,
+        allow_empty: false,
+        author,
+    };
+    if !dry_run {
+        set_pending_flags(git, &review_branch, flags, verbose)?;
+    }
+
+    // A no-squash review is resumed at the commit it was at, as the earlier ones are already approved
+    let commit_key = format!("cresca.{}.commit", review_branch);
+    let resumed_commit = if no_squash && review_branch_exists && !force {
+        get_config(git, &commit_key, verbose)
+    } else {
+        None
+    };
 
     // Determine target commit for squash merge
-    let target_commit = if let Some(hash) = skip_to {
+    let target_commit = if let Some(hash) = skip_to.filter(|_| resumed_commit.is_none()) {
         // Auto-approve commits before skip_to by squash merging them
         let parent = format!("{}^", hash);
 
-        // Check if there are commits before skip_to
-        let has_earlier = run_git_command(
-            "check earlier commits",
-            &["rev-list", &format!("{}..{}", merge_base, &parent)],
-            true,
-            verbose,
-        );
+        // Check if there are commits before skip_to. A root commit has no parent, so nothing
+        // comes before it and the auto-approve merge is skipped.
+        let earlier_count = if rev_parse(git, &parent, verbose).is_err() {
+            0
+        } else {
+            rev_list(git, &format!("{}..{}", merge_base, &parent), verbose)?.len()
+        };
 
-        if !has_earlier.stdout.is_empty() {
-            run_git_command(
-                "auto-approve earlier commits",
-                &[
-                    "merge",
-                    "--squash",
-                    "--quiet",
-                    "--no-stat",
-                    "-X",
-                    "theirs",
-                    &parent,
-                ],
-                false,
+        if earlier_count > 0 {
+            // Remember how to finish the review if the auto-approve merge stops on conflicts
+            let auto_approve_message = auto_approve_commit_message(
+                git,
+                auto_approve_message,
+                earlier_count,
+                hash,
                 verbose,
             );
-            run_git_command(
+            if !dry_run {
+                set_config(
+                    git,
+                    &format!("cresca.{}.pendingMessage", review_branch),
+                    &auto_approve_message,
+                    verbose,
+                )?;
+                set_config(
+                    git,
+                    &format!("cresca.{}.pendingTarget", review_branch),
+                    stop_at.unwrap_or(from_branch),
+                    verbose,
+                )?;
+            }
+
+            if dry_run {
+                let earlier_commits = run_git_command(
+                    git,
+                    "get earlier commits",
+                    &["log", "--oneline", &format!("{}..{}", merge_base, &parent)],
+                    verbose,
+                )?;
+                println!("{}", msg(Id::CommitsToAutoApprove));
+                for commit in String::from_utf8_lossy(&earlier_commits.stdout).lines() {
+                    println!("  {}", commit);
+                }
+            }
+            let spinner = Spinner::start(fill(Id::SquashMerging, &[&parent]), progress);
+            squash_merge(
+                git,
+                &review_branch,
+                "auto-approve earlier commits",
+                &parent,
+                strategy_options,
+                detect_conflicts,
+                dry_run,
+                verbose,
+            )?;
+            drop(spinner);
+            check_interrupt()?;
+            commit(
+                git,
                 "commit auto-approved changes",
-                &["commit", "--quiet", "-m", "Auto-approve earlier commits"],
-                false,
+                &auto_approve_message,
+                flags,
+                dry_run,
                 verbose,
-            );
+            )?;
+            if !dry_run {
+                unset_config(
+                    git,
+                    &format!("cresca.{}.pendingMessage", review_branch),
+                    verbose,
+                );
+                unset_config(
+                    git,
+                    &format!("cresca.{}.pendingTarget", review_branch),
+                    verbose,
+                );
+            }
         }
 
         // Use stop_at if specified, otherwise from_branch
@@ -203,143 +577,2734 @@ pub fn prepare_review_branch(
         // Use stop_at if specified, otherwise from_branch
         stop_at.unwrap_or(from_branch).to_string()
     };
+    // Remember the reviewed commit to notice when the development branch is rewritten
+    if !dry_run {
+        set_config(
+            git,
+            &format!("cresca.{}.target", review_branch),
+            &rev_parse(git, &target_commit, verbose)?,
+            verbose,
+        )?;
+    }
 
-    // Squash merge remaining changes
-    run_git_command(
-        "squash merge remaining changes",
-        &[
-            "merge",
-            "--squash",
-            "--quiet",
-            "--no-stat",
-            "-X",
-            "theirs",
+    if no_squash {
+        // Review the commits one by one, starting right after the auto-approved ones
+        let commit = match resumed_commit {
+            Some(commit) => Some(commit),
+            None => {
+                let commits = first_parent_commits(git, merge_base, &target_commit, verbose)?;
+                match skip_to {
+                    Some(hash) => Some(
+                        commits
+                            .into_iter()
+                            .find(|commit| commit == hash)
+                            .ok_or_else(|| CrescaError::CommitNotInRange {
+                                commit: hash.to_string(),
+                                range: format!("the first-parent history of {}", from_branch),
+                            })?,
+                    ),
+                    None => commits.into_iter().next(),
+                }
+            }
+        };
+        if let Some(commit) = commit {
+            pick_commit(
+                git,
+                &review_branch,
+                &commit,
+                strategy_options,
+                dry_run,
+                verbose,
+            )?;
+        }
+    } else {
+        if !dry_run {
+            unset_config(git, &commit_key, verbose);
+        }
+        // Squash merge remaining changes
+        let _spinner = Spinner::start(fill(Id::SquashMerging, &[&target_commit]), progress);
+        squash_merge(
+            git,
+            &review_branch,
+            "squash merge remaining changes",
             &target_commit,
-        ],
-        false,
-        verbose,
-    );
+            strategy_options,
+            detect_conflicts,
+            dry_run,
+            verbose,
+        )?;
+    }
+
+    check_interrupt()?;
+    exclude_files(git, exclude, dry_run, verbose)?;
+    auto_approve_files(git, auto_approve, flags, dry_run, verbose)?;
+    check_interrupt()?;
+    if !dry_run {
+        unset_pending_flags(git, &review_branch, verbose);
+    }
 
     // Unstage changes for review
-    run_git_command("unstage changes for review", &["reset"], false, verbose);
+    run_mutating_git_command(
+        git,
+        "unstage changes for review",
+        &["reset"],
+        dry_run,
+        verbose,
+    )?;
+    discard_changes_outside(git, paths, dry_run, verbose)?;
+    Ok(true)
 }
 
-/// Commit reviewed changes and discard unreviewed ones
+/// Build a matcher of the globs
 ///
 /// # Arguments
 ///
-/// * `verbose` - Whether to print the git command and its output.
+/// * `globs` - The globs to match paths relative to the repository root against.
+fn build_glob_set(globs: &[String]) -> Result<GlobSet, CrescaError> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).map_err(|e| CrescaError::InvalidGlob {
+            glob: glob.clone(),
+            message: e.kind().to_string(),
+        })?);
+    }
+    builder.build().map_err(|e| CrescaError::InvalidGlob {
+        glob: e.glob().unwrap_or_default().to_string(),
+        message: e.kind().to_string(),
+    })
+}
+
+/// Get the staged files matching any of the globs, as literal pathspecs
 ///
-/// # Returns
+/// # Arguments
 ///
-/// * `Ok(())` - If there are staged changes
-/// * `Err(())` - If there are no staged changes
-pub fn approve_changes(verbose: bool) -> Result<(), ()> {
-    // Check if there are staged changes
-    let has_staged_changes = run_git_command(
-        "check staged changes",
-        &["diff", "--cached"],
-        false,
-        verbose,
-    )
-    .stdout
-    .is_empty()
-    .not();
+/// * `git` - The runner of the git commands.
+/// * `globs` - The globs to match the staged files against.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn staged_files_matching(
+    git: &impl GitRunner,
+    globs: &[String],
+    verbose: u8,
+) -> Result<Vec<String>, CrescaError> {
+    let glob_set = build_glob_set(globs)?;
 
-    if has_staged_changes {
-        run_git_command(
-            "commit reviewed changes",
-            &["commit", "--quiet", "-m", "Approve reviewed changes"],
-            false,
-            verbose,
-        );
-    }
-
-    run_git_command(
-        "discard unreviewed changes",
-        &["restore", "--source=HEAD", "--worktree", "--", "."],
-        false,
+    // Renames are split so that both sides are matched on their own
+    let output = run_git_command(
+        git,
+        "get merged files",
+        &["diff", "--cached", "--name-only", "--no-renames", "-z"],
         verbose,
-    );
-    run_git_command("discard untracked files", &["clean", "-fd"], false, verbose);
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|file| !file.is_empty() && glob_set.is_match(file))
+        .map(|file| format!(":(top,literal){}", file))
+        .collect())
+}
 
-    match has_staged_changes {
-        true => Ok(()),
-        false => Err(()),
+/// Revert the staged files matching any of the globs to HEAD, leaving them out of the review
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `globs` - The globs of the files to exclude. Nothing is excluded if empty.
+/// * `dry_run` - Whether to print the git commands instead of running them.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn exclude_files(
+    git: &impl GitRunner,
+    globs: &[String],
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if globs.is_empty() {
+        return Ok(());
+    }
+    let files = staged_files_matching(git, globs, verbose)?;
+    if files.is_empty() {
+        return Ok(());
     }
-}
 
-/// Review status information
-pub struct ReviewStatus {
-    pub from_branch: String,
-    pub file_count: usize,
-    pub insertions: usize,
-    pub deletions: usize,
-    pub files: Vec<String>,
+    let mut args = vec!["restore", "--source=HEAD", "--staged", "--worktree", "--"];
+    args.extend(files.iter().map(String::as_str));
+    run_mutating_git_command(git, "revert excluded files", &args, dry_run, verbose)?;
+    Ok(())
 }
 
-/// Get review status (remaining diff stats)
+/// Commit the staged files matching any of the globs, leaving the other changes unstaged
 ///
 /// # Arguments
 ///
-/// * `from_branch` - The development branch to compare against.
-/// * `verbose` - Whether to print the git command and its output.
+/// * `git` - The runner of the git commands.
+/// * `globs` - The globs of the files to approve. Nothing is approved if empty.
+/// * `flags` - The flags of the auto-approve commit.
+/// * `dry_run` - Whether to print the git commands instead of running them.
+/// * `verbose` - The verbosity level (see `run_git_command`).
 ///
 /// # Returns
 ///
-/// * `ReviewStatus` - The remaining diff statistics
-pub fn get_review_status(from_branch: &str, verbose: bool) -> ReviewStatus {
-    // Get diff stats summary (use HEAD..branch for direct comparison, not HEAD...branch)
-    let stat_output = run_git_command(
-        "get diff stats",
-        &["diff", "--stat", "HEAD", from_branch],
-        false,
-        verbose,
-    );
-    let stat_str = String::from_utf8_lossy(&stat_output.stdout);
-
-    // Parse stats from last line (e.g., " 4 files changed, 7 insertions(+), 2 deletions(-)")
-    let mut file_count = 0;
-    let mut insertions = 0;
-    let mut deletions = 0;
-
-    if let Some(last_line) = stat_str.lines().last() {
-        for part in last_line.split(',') {
-            let part = part.trim();
-            if part.contains("file") {
-                if let Some(num) = part.split_whitespace().next() {
-                    file_count = num.parse().unwrap_or(0);
-                }
-            } else if part.contains("insertion") {
-                if let Some(num) = part.split_whitespace().next() {
-                    insertions = num.parse().unwrap_or(0);
-                }
-            } else if part.contains("deletion") {
-                if let Some(num) = part.split_whitespace().next() {
-                    deletions = num.parse().unwrap_or(0);
-                }
-            }
-        }
+/// * `usize` - The number of approved files
+fn auto_approve_files(
+    git: &impl GitRunner,
+    globs: &[String],
+    flags: CommitFlags,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<usize, CrescaError> {
+    if globs.is_empty() {
+        return Ok(0);
+    }
+    let files = staged_files_matching(git, globs, verbose)?;
+    if files.is_empty() {
+        return Ok(0);
     }
 
-    // Get list of changed files
-    let files_output = run_git_command(
-        "get changed files",
-        &["diff", "--name-only", "HEAD", from_branch],
-        false,
+    run_mutating_git_command(
+        git,
+        "unstage merged files",
+        &["reset", "--quiet"],
+        dry_run,
         verbose,
-    );
-    let files: Vec<String> = String::from_utf8_lossy(&files_output.stdout)
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
+    )?;
+    let mut args = vec!["add", "--all", "--"];
+    args.extend(files.iter().map(String::as_str));
+    run_mutating_git_command(git, "stage auto-approved files", &args, dry_run, verbose)?;
+    commit(
+        git,
+        "commit auto-approved files",
+        &format!(
+            "Auto-approve {} file(s) matching {}",
+            files.len(),
+            globs.join(", ")
+        ),
+        flags,
+        dry_run,
+        verbose,
+    )?;
+    Ok(files.len())
+}
+
+/// Discard the unstaged changes outside of the given paths, so only the changes inside are left to review
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `paths` - Paths relative to the repository root to keep the changes of. Nothing is discarded if empty.
+/// * `dry_run` - Whether to print the git commands instead of running them.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn discard_changes_outside(
+    git: &impl GitRunner,
+    paths: &[String],
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let excludes: Vec<String> = paths
+        .iter()
+        .map(|path| format!(":(top,exclude){}", path))
         .collect();
 
-    ReviewStatus {
-        from_branch: from_branch.to_string(),
-        file_count,
-        insertions,
-        deletions,
-        files,
+    let mut args = vec!["restore", "--worktree", "--", ":/"];
+    args.extend(excludes.iter().map(String::as_str));
+    run_mutating_git_command(
+        git,
+        "discard changes outside of the paths",
+        &args,
+        dry_run,
+        verbose,
+    )?;
+
+    let mut args = vec!["clean", "-fdq", "--", ":/"];
+    args.extend(excludes.iter().map(String::as_str));
+    run_mutating_git_command(
+        git,
+        "discard new files outside of the paths",
+        &args,
+        dry_run,
+        verbose,
+    )?;
+    Ok(())
+}
+
+/// Build the commit message of the auto-approve commit
+///
+/// An `Auto-approved-by` trailer with the reviewer's `user.name` is added if it is set.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `message` - Optional commit message. Defaults to a summary of the auto-approved commits.
+/// * `count` - The number of auto-approved commits.
+/// * `skip_to` - The full hash of the commit to skip to.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn auto_approve_commit_message(
+    git: &impl GitRunner,
+    message: Option<&str>,
+    count: usize,
+    skip_to: &str,
+    verbose: u8,
+) -> String {
+    let mut message = message.map(str::to_string).unwrap_or_else(|| {
+        fill_plural(
+            count,
+            Id::AutoApproveCommit,
+            Id::AutoApproveCommits,
+            &[&count, &&skip_to[..7]],
+        )
+    });
+    if let Some(reviewer) = get_config(git, "user.name", verbose) {
+        message.push_str(&format!("\n\nAuto-approved-by: {}", reviewer));
+    }
+    message
+}
+
+/// The commits of a review between the merge base and the development branch
+struct CommitRange {
+    /// The merge base of the two branches.
+    merge_base: String,
+    /// The full hashes of the commits in the range, newest first.
+    commits: Vec<String>,
+    /// The full hash of the `--skip-to` commit.
+    skip_to: Option<String>,
+    /// The full hash of the `--stop-at` commit.
+    stop_at: Option<String>,
+}
+
+/// Get the commit range of a review and resolve `--skip-to` and `--stop-at` in it
+///
+/// Fails if a commit is not in the range or is ambiguous, or if `stop_at` is before `skip_to`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `skip_to` - The full or short hash of the commit to skip to.
+/// * `stop_at` - The full or short hash of the commit to stop at.
+/// * `base` - Optional commit to use as the base instead of the merge base.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn resolve_commit_range(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    skip_to: Option<&str>,
+    stop_at: Option<&str>,
+    base: Option<String>,
+    verbose: u8,
+) -> Result<CommitRange, CrescaError> {
+    // Get merge-base
+    let merge_base = match base {
+        Some(base) => base,
+        None if have_unrelated_histories(git, to_branch, from_branch, verbose) => {
+            return Err(CrescaError::UnrelatedHistories {
+                to_branch: to_branch.to_string(),
+                from_branch: from_branch.to_string(),
+            });
+        }
+        None => get_merge_base(git, to_branch, from_branch, verbose)?,
+    };
+
+    // Get valid commit range (merge_base..from_branch)
+    let valid_hashes = rev_list(git, &format!("{}..{}", merge_base, from_branch), verbose)?;
+
+    // `rev-list` prints full hashes, so the given (possibly short) hashes are matched as prefixes
+    debug_assert!(valid_hashes.iter().all(|hash| hash.len() >= 40));
+    let skip_hash = skip_to
+        .map(|hash| find_commit_in_range(hash, &valid_hashes, to_branch, from_branch))
+        .transpose()?;
+    // Only the first parent of a merge commit would be auto-approved, which splits the merged
+    // changes between the auto-approved and the reviewed ones
+    if let Some(hash) = skip_hash.filter(|hash| is_merge_commit(git, hash, verbose)) {
+        return Err(CrescaError::SkipToMergeCommit(
+            skip_to.unwrap_or(hash).to_string(),
+        ));
     }
+    let stop_hash = stop_at
+        .map(|hash| find_commit_in_range(hash, &valid_hashes, to_branch, from_branch))
+        .transpose()?;
+
+    // If skip_to is also specified, stop_at must be at or after skip_to
+    if let (Some(skip_hash), Some(hash)) = (skip_hash, stop_hash) {
+        let is_after_skip = hash == skip_hash
+            || rev_list(git, &format!("{}..{}", skip_hash, from_branch), verbose)?
+                .iter()
+                .any(|line| line == hash);
+        if !is_after_skip {
+            return Err(CrescaError::StopAtBeforeSkipTo {
+                stop_at: stop_at.unwrap_or(hash).to_string(),
+                skip_to: skip_to.unwrap_or(skip_hash).to_string(),
+            });
+        }
+    }
+
+    Ok(CommitRange {
+        skip_to: skip_hash.map(str::to_string),
+        stop_at: stop_hash.map(str::to_string),
+        commits: valid_hashes,
+        merge_base,
+    })
+}
+
+/// How a commit is handled by a review
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CommitAction {
+    /// The commit is before `--skip-to` and is approved automatically.
+    AutoApprove,
+    /// The commit is reviewed.
+    Review,
+    /// The commit is after `--stop-at` and is excluded from the review.
+    Exclude,
+}
+
+/// Preview the commits of a review without modifying the repository
+///
+/// Uses the local branches as they are; nothing is pulled.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `skip_to` - The full or short hash of the commit to skip to.
+/// * `stop_at` - The full or short hash of the commit to stop at.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<(String, CommitAction)>` - The one-line logs of the commits, newest first, and how they are handled
+pub fn preview_review(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    skip_to: Option<&str>,
+    stop_at: Option<&str>,
+    verbose: u8,
+) -> Result<Vec<(String, CommitAction)>, CrescaError> {
+    let range = resolve_commit_range(git, to_branch, from_branch, skip_to, stop_at, None, verbose)?;
+    let auto_approved = match &range.skip_to {
+        // The parent does not exist if `skip_to` is a root commit
+        Some(hash) if rev_parse(git, &format!("{}^", hash), verbose).is_err() => Vec::new(),
+        Some(hash) => rev_list(git, &format!("{}..{}^", range.merge_base, hash), verbose)?,
+        None => Vec::new(),
+    };
+    let reviewed_or_earlier = match &range.stop_at {
+        Some(hash) => rev_list(git, &format!("{}..{}", range.merge_base, hash), verbose)?,
+        None => range.commits.clone(),
+    };
+
+    let output = run_git_command(
+        git,
+        "get commits to review",
+        &[
+            "log",
+            "--format=%H %h %s",
+            &format!("{}..{}", range.merge_base, from_branch),
+        ],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (hash, oneline) = line.split_once(' ')?;
+            let action = if !reviewed_or_earlier.iter().any(|commit| commit == hash) {
+                CommitAction::Exclude
+            } else if auto_approved.iter().any(|commit| commit == hash) {
+                CommitAction::AutoApprove
+            } else {
+                CommitAction::Review
+            };
+            Some((oneline.to_string(), action))
+        })
+        .collect())
+}
+
+/// Find the commit in the range that the given hash is a prefix of
+///
+/// Fails if no commit or more than one commit matches.
+///
+/// # Arguments
+///
+/// * `hash` - The full or short commit hash.
+/// * `valid_hashes` - The full hashes of the commits in the range.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+///
+/// # Returns
+///
+/// * `&str` - The full hash of the matching commit
+fn find_commit_in_range<'a>(
+    hash: &str,
+    valid_hashes: &'a [String],
+    to_branch: &str,
+    from_branch: &str,
+) -> Result<&'a str, CrescaError> {
+    let matches: Vec<&String> = valid_hashes
+        .iter()
+        .filter(|line| line.starts_with(hash))
+        .collect();
+    match matches.as_slice() {
+        [commit] => Ok(commit),
+        [] => Err(CrescaError::CommitNotInRange {
+            commit: hash.to_string(),
+            range: format!("{}..{}", to_branch, from_branch),
+        }),
+        _ => Err(CrescaError::AmbiguousCommit {
+            commit: hash.to_string(),
+            matches: matches.into_iter().cloned().collect(),
+        }),
+    }
+}
+
+/// Get the strategy options of the merges, adding `ignore-cr-at-eol` to ignore line ending changes
+///
+/// The default `-X theirs` is only applied when no strategy option is given, so it is added
+/// explicitly along with `ignore-cr-at-eol` unless conflicts are to be detected.
+///
+/// # Arguments
+///
+/// * `strategy_options` - The strategy options given by the user.
+/// * `detect_conflicts` - Whether to stop on conflicts instead of resolving them with `-X theirs`.
+/// * `ignore_eol` - Whether to ignore carriage returns at the end of lines.
+fn merge_strategy_options(
+    strategy_options: &[String],
+    detect_conflicts: bool,
+    ignore_eol: bool,
+) -> Vec<String> {
+    let mut options = strategy_options.to_vec();
+    if ignore_eol {
+        if options.is_empty() && !detect_conflicts {
+            options.push("theirs".to_string());
+        }
+        options.push("ignore-cr-at-eol".to_string());
+    }
+    options
+}
+
+/// Get the files with unresolved merge conflicts
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn get_conflicted_files(git: &impl GitRunner, verbose: u8) -> Result<Vec<String>, CrescaError> {
+    let output = run_git_command(
+        git,
+        "get conflicted files",
+        &["diff", "--name-only", "--diff-filter=U", "-z"],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|file| !file.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Squash merge a commit into the current branch without committing
+///
+/// On conflicts, the conflicted changes are left in the working tree, the review branch is marked
+/// as conflicted for `cresca continue`, and `CrescaError::MergeConflicts` is returned.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `description` - The description of the merge.
+/// * `commit` - The commit to squash merge.
+/// * `strategy_options` - Strategy options passed to `git merge -X`.
+/// * `detect_conflicts` - Whether to stop on conflicts instead of resolving them with `-X theirs` when no strategy option is given.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+#[allow(clippy::too_many_arguments)]
+fn squash_merge(
+    git: &impl GitRunner,
+    review_branch: &str,
+    description: &str,
+    commit: &str,
+    strategy_options: &[String],
+    detect_conflicts: bool,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    // Review branches of unrelated histories start from an empty root commit
+    let mut args = vec![
+        "merge",
+        "--squash",
+        "--quiet",
+        "--no-stat",
+        "--allow-unrelated-histories",
+    ];
+    if strategy_options.is_empty() && !detect_conflicts {
+        args.extend(["-X", "theirs"]);
+    }
+    for option in strategy_options {
+        args.extend(["-X", option.as_str()]);
+    }
+    args.push(commit);
+
+    let Err(error) = run_mutating_git_command(git, description, &args, dry_run, verbose) else {
+        return Ok(());
+    };
+
+    let conflicted_files = get_conflicted_files(git, verbose)?;
+    if conflicted_files.is_empty() {
+        return Err(error.into());
+    }
+    set_config(
+        git,
+        &format!("cresca.{}.conflicted", review_branch),
+        "true",
+        verbose,
+    )?;
+
+    Err(CrescaError::MergeConflicts {
+        description: description.to_string(),
+        files: conflicted_files,
+    })
+}
+
+/// Get the reviewed commits that are no longer in the development branch, e.g. after a force-push
+///
+/// The commits reviewed so far end at the target of the last `cresca review`, which is stored in
+/// the git config. Review branches created before the target was stored are not checked.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `from_branch` - The name of the development branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<String>` - The one-line logs of the disappeared commits, newest first
+fn disappeared_commits(
+    git: &impl GitRunner,
+    review_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Vec<String> {
+    let Some(target) = get_config(git, &format!("cresca.{}.target", review_branch), verbose) else {
+        return Vec::new();
+    };
+    let is_ancestor = run_git_command(
+        git,
+        "check whether the reviewed commits are still in the development branch",
+        &["merge-base", "--is-ancestor", &target, from_branch],
+        verbose,
+    )
+    .is_ok();
+    if is_ancestor {
+        return Vec::new();
+    }
+
+    let mut args = vec!["log", "--format=%h %s", &target, "--not", from_branch];
+    let merge_base = get_config(git, &format!("cresca.{}.mergeBase", review_branch), verbose);
+    if let Some(merge_base) = &merge_base {
+        args.push(merge_base);
+    }
+    match run_git_command(git, "list the disappeared commits", &args, verbose) {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        // The old commits may have been garbage collected already
+        Err(_) => vec![target[..target.len().min(7)].to_string()],
+    }
+}
+
+/// Get the commits on the first-parent history between two revisions, oldest first
+///
+/// Merge commits stand for all the changes they merge, so the merged commits are not listed.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from` - The revision to list the commits after.
+/// * `to` - The revision to list the commits up to.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn first_parent_commits(
+    git: &impl GitRunner,
+    from: &str,
+    to: &str,
+    verbose: u8,
+) -> Result<Vec<String>, CrescaError> {
+    let output = run_git_command(
+        git,
+        "list commits to review",
+        &[
+            "rev-list",
+            "--reverse",
+            "--first-parent",
+            &format!("{}..{}", from, to),
+        ],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Apply the changes of a single commit to the review branch without committing, for a no-squash review
+///
+/// Conflicts are resolved with `-X theirs` unless other strategy options are given. Conflicts these
+/// cannot resolve, e.g. a file modified on one side and deleted on the other, are left in the working
+/// tree like those of `squash_merge`, with the commit recorded as under review for `cresca continue`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `commit` - The full hash of the commit to apply.
+/// * `strategy_options` - Strategy options passed to `git cherry-pick -X`.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn pick_commit(
+    git: &impl GitRunner,
+    review_branch: &str,
+    commit: &str,
+    strategy_options: &[String],
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let mut args = vec!["cherry-pick", "--no-commit"];
+    // The changes of a merge are the ones it brings into its first parent
+    if is_merge_commit(git, commit, verbose) {
+        args.extend(["-m", "1"]);
+    }
+    if strategy_options.is_empty() {
+        args.extend(["-X", "theirs"]);
+    }
+    for option in strategy_options {
+        args.extend(["-X", option.as_str()]);
+    }
+    args.push(commit);
+    if !dry_run {
+        set_config(
+            git,
+            &format!("cresca.{}.commit", review_branch),
+            commit,
+            verbose,
+        )?;
+    }
+    let description = format!("apply commit {}", &commit[..commit.len().min(7)]);
+    let Err(error) = run_mutating_git_command(git, &description, &args, dry_run, verbose) else {
+        return Ok(());
+    };
+
+    let conflicted_files = get_conflicted_files(git, verbose)?;
+    if conflicted_files.is_empty() {
+        return Err(error.into());
+    }
+    set_config(
+        git,
+        &format!("cresca.{}.conflicted", review_branch),
+        "true",
+        verbose,
+    )?;
+    Err(CrescaError::MergeConflicts {
+        description,
+        files: conflicted_files,
+    })
+}
+
+/// Remember the flags of the commit of the auto-approved files, in case the merge before it stops on
+/// conflicts and `cresca continue` has to create the commit
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `flags` - The flags of the commit.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn set_pending_flags(
+    git: &impl GitRunner,
+    review_branch: &str,
+    flags: CommitFlags,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if flags.sign {
+        set_config(
+            git,
+            &format!("cresca.{}.pendingSign", review_branch),
+            "true",
+            verbose,
+        )?;
+    }
+    if flags.no_verify {
+        set_config(
+            git,
+            &format!("cresca.{}.pendingNoVerify", review_branch),
+            "true",
+            verbose,
+        )?;
+    }
+    if let Some(author) = flags.author {
+        set_config(
+            git,
+            &format!("cresca.{}.pendingAuthor", review_branch),
+            author,
+            verbose,
+        )?;
+    }
+    Ok(())
+}
+
+/// Forget the flags remembered by `set_pending_flags`
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn unset_pending_flags(git: &impl GitRunner, review_branch: &str, verbose: u8) {
+    for key in ["pendingSign", "pendingNoVerify", "pendingAuthor"] {
+        unset_config(git, &format!("cresca.{}.{}", review_branch, key), verbose);
+    }
+}
+
+/// Move a no-squash review on to the next commit after the current one is approved
+///
+/// The changes of the next commit are left unstaged for review like after `cresca review`.
+/// Does nothing for squash reviews.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `author` - Optional author of the commit of the auto-approved files in the `Name <email>` format.
+/// * `sign` - Whether to sign the commit of the auto-approved files.
+/// * `no_verify` - Whether to bypass the pre-commit and commit-msg hooks for the commit of the auto-approved files.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<String>` - The one-line log of the commit now under review, or `None` if there is none left
+pub fn review_next_commit(
+    git: &impl GitRunner,
+    author: Option<&str>,
+    sign: bool,
+    no_verify: bool,
+    verbose: u8,
+) -> Result<Option<String>, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    let commit_key = format!("cresca.{}.commit", review_branch);
+    let (Some(current), Some(target)) = (
+        get_config(git, &commit_key, verbose),
+        get_config(git, &format!("cresca.{}.target", review_branch), verbose),
+    ) else {
+        return Ok(None);
+    };
+    let Some(next) = first_parent_commits(git, &current, &target, verbose)?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+
+    let strategy_options = get_config_all(
+        git,
+        &format!("cresca.{}.strategyOption", review_branch),
+        verbose,
+    );
+    let flags = CommitFlags {
+        sign,
+        no_verify,
+        allow_empty: false,
+        author,
+    };
+    set_pending_flags(git, &review_branch, flags, verbose)?;
+    pick_commit(
+        git,
+        &review_branch,
+        &next,
+        &strategy_options,
+        false,
+        verbose,
+    )?;
+    unstage_for_review(git, &review_branch, flags, verbose)?;
+    unset_pending_flags(git, &review_branch, verbose);
+    Ok(Some(commit_summary(git, &next, verbose)?))
+}
+
+/// Get the one-line log of the commit under review in a no-squash review
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<String>` - The one-line log of the commit, or `None` for squash reviews
+pub fn current_review_commit(
+    git: &impl GitRunner,
+    verbose: u8,
+) -> Result<Option<String>, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    get_config(git, &format!("cresca.{}.commit", review_branch), verbose)
+        .map(|commit| commit_summary(git, &commit, verbose))
+        .transpose()
+}
+
+/// Get the one-line log of a commit
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `commit` - The commit.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn commit_summary(git: &impl GitRunner, commit: &str, verbose: u8) -> Result<String, CrescaError> {
+    let output = run_git_command(
+        git,
+        "get commit summary",
+        &["log", "-1", "--format=%h %s", commit],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Get the path of the worktree to prepare a review branch in
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `worktree_dir` - Optional directory to create the worktrees in, relative to the repository root.
+///   Defaults to `cresca/<repository name>` in the temporary directory.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `PathBuf` - The path of the worktree
+pub fn review_worktree_path(
+    git: &impl GitRunner,
+    review_branch: &str,
+    worktree_dir: Option<&str>,
+    verbose: u8,
+) -> Result<PathBuf, CrescaError> {
+    let output = run_git_command(
+        git,
+        "get repository root",
+        &["rev-parse", "--show-toplevel"],
+        verbose,
+    )?;
+    let root = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let dir = match worktree_dir {
+        Some(dir) => root.join(dir),
+        None => std::env::temp_dir()
+            .join("cresca")
+            .join(root.file_name().unwrap_or_default()),
+    };
+    Ok(dir.join(review_branch))
+}
+
+/// Update a local branch from the remote
+///
+/// Without a worktree, the branch is switched to and pulled. With a worktree, the current checkout must stay on
+/// its branch, so the branch is fetched (fast-forward only) instead, or pulled if it is the current branch.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `branch` - The branch to update.
+/// * `remote` - The remote to pull the branch from.
+/// * `worktree` - Whether the review is prepared in a worktree.
+/// * `network` - The timeout and retries of the pull or fetch.
+/// * `dry_run` - Whether to print the git commands instead of running them.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn update_branch(
+    git: &impl GitRunner,
+    branch: &str,
+    remote: &str,
+    worktree: bool,
+    network: NetworkFlags,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if worktree {
+        // Branches checked out in the current checkout cannot be fetched into
+        if get_current_branch(git, verbose)? != branch {
+            run_network_git_command(
+                git,
+                &format!("fetch {} branch", branch),
+                &["fetch", remote, &format!("{}:{}", branch, branch)],
+                network,
+                dry_run,
+                verbose,
+            )?;
+            return Ok(());
+        }
+    } else {
+        run_mutating_git_command(
+            git,
+            &format!("switch to {} branch", branch),
+            &["switch", branch],
+            dry_run,
+            verbose,
+        )?;
+    }
+    run_network_git_command(
+        git,
+        &format!("pull {} branch", branch),
+        &["pull", remote, branch],
+        network,
+        dry_run,
+        verbose,
+    )?;
+    Ok(())
+}
+
+/// Timeout and retries of the git commands talking to a remote
+#[derive(Clone, Copy)]
+struct NetworkFlags {
+    /// How long to let each attempt run. Waits forever if `None`.
+    timeout: Option<Duration>,
+    /// How many times to retry a failed command.
+    retries: u32,
+}
+
+/// Run a git command that talks to a remote, retrying it on failure and killing each attempt
+/// after the timeout if one is given
+///
+/// The retries wait 1, 2, 4, ... seconds before they start, up to 8 seconds.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `description` - The description of the git command.
+/// * `args` - The arguments to pass to the git command.
+/// * `network` - The timeout and retries of the git command.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn run_network_git_command(
+    git: &impl GitRunner,
+    description: &str,
+    args: &[&str],
+    network: NetworkFlags,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let mut attempt = 0;
+    loop {
+        let result = match network.timeout {
+            Some(timeout) if !dry_run => {
+                run_git_command_with_timeout(git, description, args, timeout, verbose).map(|_| ())
+            }
+            _ => run_mutating_git_command(git, description, args, dry_run, verbose)
+                .map(|_| ())
+                .map_err(CrescaError::from),
+        };
+        match result {
+            // A pull interrupted along with cresca must not be retried
+            Err(e) if attempt < network.retries && !interrupt_requested() => {
+                attempt += 1;
+                let delay = Duration::from_secs(1 << (attempt - 1).min(3));
+                if verbose >= 1 {
+                    println!(
+                        "[retry {}/{} of git {} in {:?}: {}]",
+                        attempt,
+                        network.retries,
+                        args.join(" "),
+                        delay,
+                        e
+                    );
+                }
+                thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Flags of the commits created by cresca
+#[derive(Clone, Copy)]
+struct CommitFlags<'a> {
+    /// Whether to sign the commit.
+    sign: bool,
+    /// Whether to bypass the pre-commit and commit-msg hooks.
+    no_verify: bool,
+    /// Whether to commit even if nothing is staged.
+    allow_empty: bool,
+    /// The author of the commit in the `Name <email>` format. Defaults to the git identity.
+    author: Option<&'a str>,
+}
+
+/// Check that an author to override the git identity with is in the `Name <email>` format
+///
+/// `git commit --author` would otherwise search the history for a matching author.
+///
+/// # Arguments
+///
+/// * `author` - The author to check.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the author has a name and an email address.
+/// * `Err(CrescaError::InvalidAuthor)` - Otherwise.
+pub fn validate_author(author: &str) -> Result<(), CrescaError> {
+    let valid = author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| {
+            !name.trim().is_empty()
+                && email.contains('@')
+                && !email.contains(['<', '>'])
+                && !author.contains(['\n', '\0'])
+        });
+    if !valid {
+        return Err(CrescaError::InvalidAuthor(author.to_string()));
+    }
+    Ok(())
+}
+
+/// Commit the staged changes, signing the commit with `-S` if `flags.sign` is set
+///
+/// Signing is disabled explicitly otherwise, so `commit.gpgsign` only takes effect through `flags.sign`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `description` - The description of the commit.
+/// * `message` - The commit message.
+/// * `flags` - The flags of the commit.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn commit(
+    git: &impl GitRunner,
+    description: &str,
+    message: &str,
+    flags: CommitFlags,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let sign = flags.sign;
+    let sign_flag = if sign { "-S" } else { "--no-gpg-sign" };
+    let mut args = vec!["commit", "--quiet", sign_flag, "-m", message];
+    if flags.no_verify {
+        args.push("--no-verify");
+    }
+    if flags.allow_empty {
+        args.push("--allow-empty");
+    }
+    let author_arg;
+    if let Some(author) = flags.author {
+        author_arg = format!("--author={}", author);
+        args.push(&author_arg);
+    }
+    run_mutating_git_command(git, description, &args, dry_run, verbose).map_err(|e| {
+        if sign && e.stderr.contains("sign") {
+            CrescaError::SigningFailed(e)
+        } else {
+            CrescaError::Git(e)
+        }
+    })?;
+    Ok(())
+}
+
+/// Continue preparing the review branch after the conflicts of a squash merge are resolved
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn continue_review(git: &impl GitRunner, verbose: u8) -> Result<(), CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    let conflicted_key = format!("cresca.{}.conflicted", review_branch);
+    if get_config(git, &conflicted_key, verbose).is_none() {
+        return Err(CrescaError::NothingToContinue);
+    }
+
+    let conflicted_files = get_conflicted_files(git, verbose)?;
+    if !conflicted_files.is_empty() {
+        return Err(CrescaError::UnresolvedConflicts(conflicted_files));
+    }
+    unset_config(git, &conflicted_key, verbose);
+
+    // Finish the auto-approve step if it was the one stopped on conflicts. A conflicted commit of a
+    // no-squash review is only left unstaged for review like any other.
+    let message_key = format!("cresca.{}.pendingMessage", review_branch);
+    let target_key = format!("cresca.{}.pendingTarget", review_branch);
+    let author = get_config(
+        git,
+        &format!("cresca.{}.pendingAuthor", review_branch),
+        verbose,
+    );
+    let flags = CommitFlags {
+        sign: get_config(
+            git,
+            &format!("cresca.{}.pendingSign", review_branch),
+            verbose,
+        )
+        .is_some(),
+        no_verify: get_config(
+            git,
+            &format!("cresca.{}.pendingNoVerify", review_branch),
+            verbose,
+        )
+        .is_some(),
+        allow_empty: false,
+        author: author.as_deref(),
+    };
+    if let (Some(message), Some(target)) = (
+        get_config(git, &message_key, verbose),
+        get_config(git, &target_key, verbose),
+    ) {
+        commit(
+            git,
+            "commit auto-approved changes",
+            &message,
+            flags,
+            false,
+            verbose,
+        )?;
+        unset_config(git, &message_key, verbose);
+        unset_config(git, &target_key, verbose);
+        let skip_to = get_config(git, &format!("cresca.{}.skipTo", review_branch), verbose);
+        let no_squash =
+            get_config(git, &format!("cresca.{}.noSquash", review_branch), verbose).is_some();
+        let strategy_options = get_config_all(
+            git,
+            &format!("cresca.{}.strategyOption", review_branch),
+            verbose,
+        );
+        match skip_to.filter(|_| no_squash) {
+            // A no-squash review starts with the commit skipped to
+            Some(skip_to) => {
+                pick_commit(
+                    git,
+                    &review_branch,
+                    &skip_to,
+                    &strategy_options,
+                    false,
+                    verbose,
+                )?;
+            }
+            None => {
+                squash_merge(
+                    git,
+                    &review_branch,
+                    "squash merge remaining changes",
+                    &target,
+                    &strategy_options,
+                    true,
+                    false,
+                    verbose,
+                )?;
+            }
+        }
+    }
+
+    unstage_for_review(git, &review_branch, flags, verbose)?;
+    unset_pending_flags(git, &review_branch, verbose);
+    Ok(())
+}
+
+/// Leave the staged changes for review with the settings stored for the review branch
+///
+/// Excluded files are reverted, auto-approved files are committed, the other changes are unstaged,
+/// and the changes outside of the paths the review is limited to are discarded.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `flags` - The flags of the commit of the auto-approved files.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn unstage_for_review(
+    git: &impl GitRunner,
+    review_branch: &str,
+    flags: CommitFlags,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let globs = get_config_all(git, &format!("cresca.{}.exclude", review_branch), verbose);
+    exclude_files(git, &globs, false, verbose)?;
+    let globs = get_config_all(
+        git,
+        &format!("cresca.{}.autoApprove", review_branch),
+        verbose,
+    );
+    auto_approve_files(git, &globs, flags, false, verbose)?;
+
+    // Unstage changes for review
+    run_git_command(git, "unstage changes for review", &["reset"], verbose)?;
+    let paths = get_config_all(git, &format!("cresca.{}.path", review_branch), verbose);
+    discard_changes_outside(git, &paths, false, verbose)?;
+    Ok(())
+}
+
+/// Commit reviewed changes and discard unreviewed ones
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `message` - Optional commit message. Defaults to the message of the commit under review in a
+///   no-squash review, or `DEFAULT_APPROVE_MESSAGE` otherwise.
+/// * `author` - Optional author of the approve commit in the `Name <email>` format. Defaults to the git identity.
+/// * `sign` - Whether to sign the approve commit.
+/// * `no_verify` - Whether to bypass the pre-commit and commit-msg hooks for the approve commit.
+/// * `allow_empty` - Whether to create an empty approve commit if nothing is staged.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether an approve commit was created
+/// * `Err(CrescaError)` - If a git command failed
+pub fn approve_changes(
+    git: &impl GitRunner,
+    message: Option<&str>,
+    author: Option<&str>,
+    sign: bool,
+    no_verify: bool,
+    allow_empty: bool,
+    verbose: u8,
+) -> Result<bool, CrescaError> {
+    if let Some(author) = author {
+        validate_author(author)?;
+    }
+
+    // Check if there are staged changes
+    let has_staged_changes =
+        run_git_command(git, "check staged changes", &["diff", "--cached"], verbose)?
+            .stdout
+            .is_empty()
+            .not();
+
+    let approved = has_staged_changes || allow_empty;
+    if approved {
+        // A no-squash review keeps the message of the commit under review
+        let message = match message {
+            Some(message) => message.to_string(),
+            None => match get_config(
+                git,
+                &format!("cresca.{}.commit", get_current_branch(git, verbose)?),
+                verbose,
+            ) {
+                Some(commit) => String::from_utf8_lossy(
+                    &run_git_command(
+                        git,
+                        "get commit message",
+                        &["log", "-1", "--format=%B", &commit],
+                        verbose,
+                    )?
+                    .stdout,
+                )
+                .trim_end()
+                .to_string(),
+                None => DEFAULT_APPROVE_MESSAGE.to_string(),
+            },
+        };
+        commit(
+            git,
+            "commit reviewed changes",
+            &message,
+            CommitFlags {
+                sign,
+                no_verify,
+                allow_empty: !has_staged_changes,
+                author,
+            },
+            false,
+            verbose,
+        )?;
+    }
+
+    // Also reset the index to drop intent-to-add entries left by `stage_hunks_interactively`.
+    // Unlike `restore -- .`, this also works when HEAD has an empty tree.
+    run_git_command(
+        git,
+        "discard unreviewed changes",
+        &["reset", "--hard", "--quiet"],
+        verbose,
+    )?;
+    // `git clean -d` removes untracked directories as a whole, so the kept files are ignored instead
+    let kept_files = excluded_untracked_files(git, verbose)?;
+    let mut args = vec!["clean", "-fd"];
+    for file in &kept_files {
+        args.extend(["-e", file]);
+    }
+    run_git_command(git, "discard untracked files", &args, verbose)?;
+
+    Ok(approved)
+}
+
+/// Get the untracked files excluded from the review of the current branch, as anchored ignore patterns
+///
+/// These files are not part of the review, so they are kept when the unreviewed changes are discarded.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn excluded_untracked_files(git: &impl GitRunner, verbose: u8) -> Result<Vec<String>, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    let globs = get_config_all(git, &format!("cresca.{}.exclude", review_branch), verbose);
+    if globs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let glob_set = build_glob_set(&globs)?;
+
+    let output = run_git_command(
+        git,
+        "get untracked files",
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--full-name",
+            "-z",
+            ":/",
+        ],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|file| !file.is_empty() && glob_set.is_match(file))
+        .map(|file| {
+            let escaped: String = file
+                .chars()
+                .flat_map(|c| match c {
+                    '\\' | '*' | '?' | '[' => vec!['\\', c],
+                    _ => vec![c],
+                })
+                .collect();
+            format!("/{}", escaped)
+        })
+        .collect())
+}
+
+/// Ask whether to discard the unreviewed changes left in the working tree
+///
+/// Does not ask and returns `true` if there is nothing to discard or stdin is not a terminal.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `bool` - Whether to continue
+pub fn confirm_discarding_changes(git: &impl GitRunner, verbose: u8) -> Result<bool, CrescaError> {
+    if !io::stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    // Files with unstaged or untracked changes (`XY path`, where Y is the working tree status)
+    let output = run_git_command(
+        git,
+        "get unreviewed files",
+        &["status", "--porcelain", "--untracked-files=all"],
+        verbose,
+    )?;
+    let count = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.chars().nth(1).is_some_and(|status| status != ' '))
+        .count();
+    if count == 0 {
+        return Ok(true);
+    }
+
+    print!(
+        "{}",
+        fill(Id::ConfirmDiscard, &[&count.to_string().yellow()])
+    );
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok();
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Ask whether to recreate an existing review branch, discarding its approvals
+///
+/// Does not ask and returns `true` if the review branch does not exist or stdin is not a terminal.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `bool` - Whether to continue
+pub fn confirm_recreating_review_branch(
+    git: &impl GitRunner,
+    review_branch: &str,
+    verbose: u8,
+) -> bool {
+    if !io::stdin().is_terminal() || !branch_exists(git, review_branch, verbose) {
+        return true;
+    }
+
+    print!("{}", fill(Id::ConfirmRecreate, &[&review_branch.yellow()]));
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok();
+    answer.trim().eq_ignore_ascii_case("y")
+}
+
+/// Stage all remaining changes including untracked files
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn stage_all_changes(git: &impl GitRunner, verbose: u8) -> Result<(), CrescaError> {
+    run_git_command(git, "stage all changes", &["add", "--all"], verbose)?;
+    stage_executable_bits(git, &[":/"], verbose)
+}
+
+/// Stage the changes of the given paths for approval
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `paths` - The pathspecs to stage, e.g. `.` or `src/*.rs`.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `usize` - The number of files staged for approval
+pub fn stage_paths(
+    git: &impl GitRunner,
+    paths: &[String],
+    verbose: u8,
+) -> Result<usize, CrescaError> {
+    let mut args = vec!["add", "--all", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(git, "stage changes", &args, verbose)?;
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    stage_executable_bits(git, &paths, verbose)?;
+    count_staged_files(git, verbose)
+}
+
+/// Stage the executable bits of the development branch when git ignores them in the working tree
+///
+/// With `core.fileMode=false`, `git add` keeps the executable bit of the index, so a change of
+/// the bit alone can never be staged. The bits are taken from the reviewed commit of the
+/// development branch instead. Does nothing with `core.fileMode=true`, the default.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `pathspecs` - The pathspecs of the files being staged.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn stage_executable_bits(
+    git: &impl GitRunner,
+    pathspecs: &[&str],
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let ignores_file_mode = run_git_command(
+        git,
+        "read core.fileMode config",
+        &["config", "--type=bool", "--get", "core.fileMode"],
+        verbose,
+    )
+    .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "false");
+    if !ignores_file_mode {
+        return Ok(());
+    }
+    let review_branch = get_current_branch(git, verbose)?;
+    let Some(target) = get_config(git, &format!("cresca.{}.target", review_branch), verbose) else {
+        return Ok(());
+    };
+
+    // Each entry of the raw diff is `:<old mode> <new mode> <old blob> <new blob> <status>` and the path
+    let mut args = vec![
+        "diff",
+        "--raw",
+        "--no-renames",
+        "-z",
+        "--cached",
+        "-R",
+        &target,
+        "--",
+    ];
+    args.extend(pathspecs);
+    let output = run_git_command(git, "get the changed file modes", &args, verbose)?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut entries = output.split('\0');
+    while let (Some(meta), Some(path)) = (entries.next(), entries.next()) {
+        let mut fields = meta.trim_start_matches(':').split(' ');
+        let (Some(index_mode), Some(target_mode)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let chmod = match (index_mode, target_mode) {
+            ("100644", "100755") => "--chmod=+x",
+            ("100755", "100644") => "--chmod=-x",
+            _ => continue,
+        };
+        run_git_command(
+            git,
+            &format!("stage the executable bit of {}", path),
+            &["add", chmod, "--", &format!(":(top,literal){}", path)],
+            verbose,
+        )?;
+    }
+    Ok(())
+}
+
+/// Unstage the changes of the given paths, so they are no longer approved
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `paths` - The pathspecs to unstage, e.g. `.` or `src/*.rs`.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `usize` - The number of files still staged for approval
+pub fn unstage_paths(
+    git: &impl GitRunner,
+    paths: &[String],
+    verbose: u8,
+) -> Result<usize, CrescaError> {
+    let mut args = vec!["restore", "--staged", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(git, "unstage changes", &args, verbose)?;
+    count_staged_files(git, verbose)
+}
+
+/// Stage exactly the listed files, skipping the ones without remaining changes
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `files` - The files to stage, relative to the repository root.
+/// * `remaining_files` - The files with remaining changes (see `ReviewStatus::files`).
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<String>` - The listed files that were skipped
+pub fn stage_listed_files(
+    git: &impl GitRunner,
+    files: &[String],
+    remaining_files: &[String],
+    verbose: u8,
+) -> Result<Vec<String>, CrescaError> {
+    // Renamed files are listed as `old -> new`
+    let remaining: Vec<&str> = remaining_files
+        .iter()
+        .flat_map(|file| file.split(" -> "))
+        .collect();
+    let (files, skipped): (Vec<&String>, Vec<&String>) = files
+        .iter()
+        .partition(|file| remaining.contains(&file.as_str()));
+
+    if !files.is_empty() {
+        let pathspecs: Vec<String> = files
+            .iter()
+            .map(|file| format!(":(top,literal){}", file))
+            .collect();
+        let mut args = vec!["add", "--all", "--"];
+        args.extend(pathspecs.iter().map(String::as_str));
+        run_git_command(git, "stage listed files", &args, verbose)?;
+        stage_executable_bits(git, &args[3..], verbose)?;
+    }
+    Ok(skipped.into_iter().cloned().collect())
+}
+
+/// Count the files staged for approval
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn count_staged_files(git: &impl GitRunner, verbose: u8) -> Result<usize, CrescaError> {
+    let output = run_git_command(
+        git,
+        "get staged files",
+        &["diff", "--cached", "--name-only", "-z"],
+        verbose,
+    )?;
+    Ok(output
+        .stdout
+        .split(|&byte| byte == 0)
+        .filter(|file| !file.is_empty())
+        .count())
+}
+
+/// Ask whether to stage each file and stage the accepted ones
+///
+/// Does nothing if stdin is not a terminal.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `files` - The files to ask about.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn stage_files_interactively(
+    git: &impl GitRunner,
+    files: &[String],
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if !io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        fill(
+            Id::StageEachFile,
+            &[&"y".green(), &"n".yellow(), &"q".red()]
+        )
+    );
+    let mut lines = io::stdin().lock().lines();
+    for file in files {
+        let answer = loop {
+            print!("  {} [y/n/q] ", file);
+            io::stdout().flush().ok();
+            let Some(Ok(line)) = lines.next() else {
+                break "q".to_string();
+            };
+            let answer = line.trim().to_lowercase();
+            if ["y", "n", "q"].contains(&answer.as_str()) {
+                break answer;
+            }
+        };
+        match answer.as_str() {
+            "y" => {
+                // Stage both paths of a rename (`old -> new`)
+                let mut args = vec!["add", "--all", "--"];
+                args.extend(file.split(" -> "));
+                run_git_command(git, &format!("stage {}", file), &args, verbose)?;
+                stage_executable_bits(git, &args[3..], verbose)?;
+            }
+            "q" => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Choose the hunks to stage with `git add --patch`
+///
+/// Untracked files are marked as intent-to-add first so that their contents can be staged too.
+/// Unstaged hunks are discarded by `approve_changes`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn stage_hunks_interactively(git: &impl GitRunner, verbose: u8) -> Result<(), CrescaError> {
+    run_git_command(
+        git,
+        "mark untracked files as intent-to-add",
+        &["add", "--intent-to-add", "--", "."],
+        verbose,
+    )?;
+    run_interactive_git_command(git, "stage hunks", &["add", "--patch"], verbose)?;
+    Ok(())
+}
+
+/// Review status information
+///
+/// This is also the schema of `cresca status --json`, so it can be deserialized from its output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewStatus {
+    /// The development branch being reviewed.
+    pub from_branch: String,
+    /// Number of files with remaining changes.
+    pub file_count: usize,
+    /// Number of remaining inserted lines.
+    pub insertions: usize,
+    /// Number of remaining deleted lines.
+    pub deletions: usize,
+    /// Number of binary files with remaining changes.
+    pub binary_files: usize,
+    /// The files with remaining changes.
+    pub files: Vec<String>,
+    /// The diff stats of each file with remaining changes.
+    pub file_stats: Vec<FileStat>,
+    /// Number of inserted lines in the whole PR.
+    pub total_insertions: usize,
+    /// Number of deleted lines in the whole PR.
+    pub total_deletions: usize,
+    /// Percentage of the changed lines of the whole PR that are reviewed.
+    pub reviewed_percent: u32,
+}
+
+impl ReviewStatus {
+    /// Number of changed lines in the whole PR
+    pub fn total_lines(&self) -> usize {
+        self.total_insertions + self.total_deletions
+    }
+
+    /// Number of changed lines already reviewed
+    pub fn reviewed_lines(&self) -> usize {
+        self.total_lines()
+            .saturating_sub(self.insertions + self.deletions)
+    }
+
+    /// Render the status with a template of `{field}` placeholders
+    ///
+    /// The placeholders are the fields of `ReviewStatus` except `files` and `file_stats`. Use `{{` and `}}`
+    /// for literal braces.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template to render (e.g. `{file_count} files, {insertions}+/{deletions}-`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The rendered template
+    /// * `Err(CrescaError)` - If the template has an unknown or unclosed placeholder
+    pub fn format(&self, template: &str) -> Result<String, CrescaError> {
+        let mut rendered = String::new();
+        let mut rest = template;
+        while let Some(index) = rest.find(['{', '}']) {
+            rendered.push_str(&rest[..index]);
+            let brace = &rest[index..index + 1];
+            rest = &rest[index + 1..];
+            if let Some(after) = rest.strip_prefix(brace) {
+                rendered.push_str(brace);
+                rest = after;
+                continue;
+            }
+            if brace == "}" {
+                return Err(CrescaError::InvalidFormat(
+                    "unmatched `}`; use `}}` for a literal brace".to_string(),
+                ));
+            }
+            let Some(end) = rest.find('}') else {
+                return Err(CrescaError::InvalidFormat(
+                    "unclosed `{`; use `{{` for a literal brace".to_string(),
+                ));
+            };
+            let value = match &rest[..end] {
+                "from_branch" => self.from_branch.clone(),
+                "file_count" => self.file_count.to_string(),
+                "insertions" => self.insertions.to_string(),
+                "deletions" => self.deletions.to_string(),
+                "binary_files" => self.binary_files.to_string(),
+                "total_insertions" => self.total_insertions.to_string(),
+                "total_deletions" => self.total_deletions.to_string(),
+                "reviewed_percent" => self.reviewed_percent.to_string(),
+                name => {
+                    return Err(CrescaError::InvalidFormat(format!(
+                        "unknown placeholder `{{{}}}`",
+                        name
+                    )))
+                }
+            };
+            rendered.push_str(&value);
+            rest = &rest[end + 1..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+/// The files a review is limited to, as stored in the review branch info
+struct ReviewScope {
+    /// The paths the review is limited to, or all paths if empty.
+    paths: Vec<String>,
+    /// The excluded files.
+    exclude: GlobSet,
+    /// Whether CR at the end of lines is ignored.
+    ignore_eol: bool,
+}
+
+impl ReviewScope {
+    /// Read the scope of a review branch
+    ///
+    /// # Arguments
+    ///
+    /// * `git` - The runner of the git commands.
+    /// * `review_branch` - The name of the review branch.
+    /// * `verbose` - The verbosity level (see `run_git_command`).
+    fn of(git: &impl GitRunner, review_branch: &str, verbose: u8) -> Result<Self, CrescaError> {
+        Ok(ReviewScope {
+            paths: get_config_all(git, &format!("cresca.{}.path", review_branch), verbose),
+            exclude: build_glob_set(&get_config_all(
+                git,
+                &format!("cresca.{}.exclude", review_branch),
+                verbose,
+            ))?,
+            ignore_eol: get_config(git, &format!("cresca.{}.ignoreEol", review_branch), verbose)
+                .is_some(),
+        })
+    }
+
+    /// Get the per-file stats of the changes in the scope between two commits
+    ///
+    /// # Arguments
+    ///
+    /// * `git` - The runner of the git commands.
+    /// * `from` - The commit to diff from.
+    /// * `to` - The commit to diff to.
+    /// * `verbose` - The verbosity level (see `run_git_command`).
+    fn file_stats(
+        &self,
+        git: &impl GitRunner,
+        from: &str,
+        to: &str,
+        verbose: u8,
+    ) -> Result<Vec<FileStat>, CrescaError> {
+        Ok(
+            get_file_stats(git, from, to, &self.paths, self.ignore_eol, verbose)?
+                .into_iter()
+                .filter(|file| {
+                    !file
+                        .path
+                        .split(" -> ")
+                        .any(|path| self.exclude.is_match(path))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the changed files excluded from the scope between two commits, as pathspecs excluding them
+    ///
+    /// # Arguments
+    ///
+    /// * `git` - The runner of the git commands.
+    /// * `from` - The commit to diff from.
+    /// * `to` - The commit to diff to.
+    /// * `verbose` - The verbosity level (see `run_git_command`).
+    fn excluded_pathspecs(
+        &self,
+        git: &impl GitRunner,
+        from: &str,
+        to: &str,
+        verbose: u8,
+    ) -> Result<Vec<String>, CrescaError> {
+        if self.exclude.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(
+            get_file_stats(git, from, to, &[], self.ignore_eol, verbose)?
+                .iter()
+                .flat_map(|file| file.path.split(" -> "))
+                .filter(|path| self.exclude.is_match(path))
+                .map(|path| format!(":(top,exclude,literal){}", path))
+                .collect(),
+        )
+    }
+}
+
+/// Get review status (remaining diff stats and review progress)
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The review branch to get the status of, e.g. `HEAD` for the current branch.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to compare against.
+/// * `against` - Optional commit to compare against instead of the tip of `from_branch`, e.g. the
+///   head of the PR when the review started.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `ReviewStatus` - The remaining diff statistics
+pub fn get_review_status(
+    git: &impl GitRunner,
+    review_branch: &str,
+    to_branch: &str,
+    from_branch: &str,
+    against: Option<&str>,
+    verbose: u8,
+) -> Result<ReviewStatus, CrescaError> {
+    let target = match against {
+        Some(commit) => rev_parse(git, commit, verbose)
+            .map_err(|_| CrescaError::NotACommit(commit.to_string()))?,
+        None => from_branch.to_string(),
+    };
+    let target = target.as_str();
+
+    // Only the paths the review is limited to count, and excluded files do not
+    let branch_name = if review_branch == "HEAD" {
+        get_current_branch(git, verbose)?
+    } else {
+        review_branch.to_string()
+    };
+    let scope = ReviewScope::of(git, &branch_name, verbose)?;
+    let file_stats_of = |from: &str| scope.file_stats(git, from, target, verbose);
+
+    // The remaining diff and the size of the whole PR are independent, so they are computed in
+    // parallel. Errors are reported in a fixed order, remaining diff first.
+    let (file_stats, total) = thread::scope(|scope| {
+        // Get the size of the whole PR from the base of the review branch, which is everything for
+        // unrelated histories
+        let total = scope.spawn(|| -> Result<DiffStats, CrescaError> {
+            let merge_base = match review_merge_base(git, &branch_name, to_branch, target, verbose)
+            {
+                Ok(merge_base) => merge_base,
+                Err(_) if have_unrelated_histories(git, to_branch, target, verbose) => {
+                    empty_tree(git, verbose)?
+                }
+                Err(e) => return Err(e),
+            };
+            Ok(DiffStats::from(file_stats_of(&merge_base)?.as_slice()))
+        });
+
+        // Use review_branch..branch for direct comparison, not review_branch...branch.
+        // The stats, the file list and the file stats all come from a single diff to stay consistent.
+        let file_stats = file_stats_of(review_branch);
+        let total = total
+            .join()
+            .expect("Failed to compute the size of the review");
+        Ok::<_, CrescaError>((file_stats?, total?))
+    })?;
+    let remaining = DiffStats::from(file_stats.as_slice());
+    let files = file_stats.iter().map(|file| file.path.clone()).collect();
+
+    let mut status = ReviewStatus {
+        from_branch: from_branch.to_string(),
+        file_count: remaining.file_count,
+        insertions: remaining.insertions,
+        deletions: remaining.deletions,
+        binary_files: remaining.binary_files,
+        files,
+        file_stats,
+        total_insertions: total.insertions,
+        total_deletions: total.deletions,
+        reviewed_percent: 100,
+    };
+    // Nothing to review counts as fully reviewed
+    if status.total_lines() > 0 {
+        status.reviewed_percent = (status.reviewed_lines() * 100 / status.total_lines()) as u32;
+    }
+    Ok(status)
+}
+
+/// Switch to the review branch of the given branches
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `prefix` - The prefix of review branch names.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `String` - The name of the review branch
+pub fn switch_review_branch(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    prefix: &str,
+    verbose: u8,
+) -> Result<String, CrescaError> {
+    let review_branch = review_branch_name(prefix, to_branch, from_branch);
+    if !branch_exists(git, &review_branch, verbose) {
+        return Err(CrescaError::NoReviewBranch {
+            to_branch: to_branch.to_string(),
+            from_branch: from_branch.to_string(),
+        });
+    }
+
+    // Unreviewed changes are expected on the review branch itself
+    if get_current_branch(git, verbose).is_ok_and(|branch| branch == review_branch) {
+        return Ok(review_branch);
+    }
+    if !is_clean(git, verbose)? {
+        return Err(CrescaError::DirtyWorkingTree);
+    }
+
+    run_git_command(
+        git,
+        &format!("switch to {} branch", review_branch),
+        &["switch", "--quiet", &review_branch],
+        verbose,
+    )?;
+    Ok(review_branch)
+}
+
+/// Abort the review by discarding all changes and deleting the review branch
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `remote` - The remote whose default branch is switched back to if `to_branch` has been deleted.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `String` - The branch that was switched back to
+pub fn abort_review(
+    git: &impl GitRunner,
+    to_branch: &str,
+    remote: &str,
+    verbose: u8,
+) -> Result<String, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+
+    // Fall back to the default branch if to_branch has been deleted
+    let return_branch = if branch_exists(git, to_branch, verbose) {
+        to_branch.to_string()
+    } else {
+        get_default_branch(git, remote, verbose)
+            .ok_or_else(|| CrescaError::BranchNotFound(to_branch.to_string()))?
+    };
+
+    run_git_command(
+        git,
+        "discard changes",
+        &["reset", "--hard", "--quiet", "HEAD"],
+        verbose,
+    )?;
+    run_git_command(git, "discard untracked files", &["clean", "-fd"], verbose)?;
+    run_git_command(
+        git,
+        &format!("switch to {} branch", return_branch),
+        &["switch", &return_branch],
+        verbose,
+    )?;
+    delete_review_branch(git, &review_branch, verbose)?;
+
+    Ok(return_branch)
+}
+
+/// Merge the approved changes of the review branch into the target branch and switch to it
+///
+/// Unreviewed changes left in the working tree are discarded. The target branch is fast-forwarded if
+/// possible, and merged otherwise.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch being reviewed.
+/// * `partial` - Whether to finalize even if unreviewed changes remain.
+/// * `delete` - Whether to delete the review branch afterwards.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn finalize_review(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    partial: bool,
+    delete: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+
+    if !partial {
+        // Only the changes the review is limited to have to be reviewed
+        let remaining = ReviewScope::of(git, &review_branch, verbose)?.file_stats(
+            git,
+            "HEAD",
+            from_branch,
+            verbose,
+        )?;
+        if !remaining.is_empty() {
+            return Err(CrescaError::UnreviewedChanges(remaining.len()));
+        }
+    }
+
+    run_git_command(
+        git,
+        "discard unreviewed changes",
+        &["reset", "--hard", "--quiet"],
+        verbose,
+    )?;
+    run_git_command(git, "discard untracked files", &["clean", "-fd"], verbose)?;
+    run_git_command(
+        git,
+        &format!("switch to {} branch", to_branch),
+        &["switch", to_branch],
+        verbose,
+    )?;
+    let message = format!(
+        "Merge approved changes of {} into {}",
+        from_branch, to_branch
+    );
+    if let Err(e) = run_git_command(
+        git,
+        "merge approved changes",
+        &["merge", "--ff", "--quiet", "-m", &message, &review_branch],
+        verbose,
+    ) {
+        // Leave the target branch as it was
+        let _ = run_git_command(git, "abort merge", &["merge", "--abort"], verbose);
+        run_git_command(
+            git,
+            "switch back to review branch",
+            &["switch", &review_branch],
+            verbose,
+        )?;
+        return Err(e.into());
+    }
+
+    if delete {
+        delete_review_branch(git, &review_branch, verbose)?;
+    }
+    Ok(())
+}
+
+/// Delete a review branch and its stored review branch info
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn delete_review_branch(
+    git: &impl GitRunner,
+    review_branch: &str,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    run_git_command(
+        git,
+        &format!("delete {} branch", review_branch),
+        &["branch", "-D", review_branch],
+        verbose,
+    )?;
+    remove_review_branch_info(git, review_branch, verbose);
+    Ok(())
+}
+
+/// Undo the most recent approval by moving its changes back to the working tree as unstaged changes
+///
+/// The auto-approve commit created by `--skip-to` cannot be undone.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `String` - The one-line log of the undone commit
+pub fn undo_approval(
+    git: &impl GitRunner,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<String, CrescaError> {
+    let (approved_count, has_auto_approve) = count_approvals(git, from_branch, verbose)?;
+    if approved_count == 0 {
+        return Err(CrescaError::NothingToUndo {
+            auto_approved: has_auto_approve,
+        });
+    }
+
+    let undone_commit = run_git_command(
+        git,
+        "get the most recent approval",
+        &["log", "--oneline", "-1", "HEAD"],
+        verbose,
+    )?;
+    run_git_command(
+        git,
+        "undo the most recent approval",
+        &["reset", "--soft", "--quiet", "HEAD^"],
+        verbose,
+    )?;
+    run_git_command(
+        git,
+        "unstage undone changes",
+        &["reset", "--quiet"],
+        verbose,
+    )?;
+
+    Ok(String::from_utf8_lossy(&undone_commit.stdout)
+        .trim()
+        .to_string())
+}
+
+/// Get the merge-base a review branch was created from
+///
+/// The merge-base stored when the review branch was created is preferred, as unrelated histories
+/// reviewed with `--allow-unrelated` have no merge-base of their own.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The review branch.
+/// * `to_branch` - The branch to compute the merge-base with if none is stored.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn review_merge_base(
+    git: &impl GitRunner,
+    review_branch: &str,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<String, CrescaError> {
+    match get_config(git, &format!("cresca.{}.mergeBase", review_branch), verbose) {
+        Some(merge_base) => Ok(merge_base),
+        None => Ok(get_merge_base(git, to_branch, from_branch, verbose)?),
+    }
+}
+
+/// Count the approve commits on the current review branch
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `(usize, bool)` - The number of approve commits, not counting the auto-approve commit, and
+///   whether the review branch has an auto-approve commit
+fn count_approvals(
+    git: &impl GitRunner,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<(usize, bool), CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+
+    // The review branch is based on the merge-base of the review, which is not changed by approvals
+    let merge_base = review_merge_base(git, &review_branch, "HEAD", from_branch, verbose)?;
+    let commit_count = rev_list(git, &format!("{}..HEAD", merge_base), verbose)?.len();
+
+    // The auto-approve commit is the first commit of the review branch if earlier commits were skipped
+    let has_auto_approve = get_config(git, &format!("cresca.{}.skipTo", review_branch), verbose)
+        .is_some_and(|hash| {
+            rev_list(git, &format!("{}..{}^", merge_base, hash), verbose)
+                .is_ok_and(|commits| !commits.is_empty())
+        });
+
+    Ok((
+        commit_count.saturating_sub(usize::from(has_auto_approve)),
+        has_auto_approve,
+    ))
+}
+
+/// Move files approved in the most recent approval back to the working tree as unstaged changes
+///
+/// The files are removed from the most recent approve commit, which is dropped if nothing else is
+/// left in it, or recreated with the same message like `approve_changes` would create it otherwise.
+/// Files approved in earlier commits cannot be unapproved this way, as the later approvals may
+/// depend on them.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `paths` - The paths of the files to unapprove.
+/// * `author` - Optional author of the recreated approve commit in the `Name <email>` format. Defaults to the git identity.
+/// * `sign` - Whether to sign the recreated approve commit.
+/// * `no_verify` - Whether to bypass the pre-commit and commit-msg hooks for the recreated approve commit.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<String>` - The unapproved files, relative to the repository root
+pub fn unapprove_paths(
+    git: &impl GitRunner,
+    from_branch: &str,
+    paths: &[String],
+    author: Option<&str>,
+    sign: bool,
+    no_verify: bool,
+    verbose: u8,
+) -> Result<Vec<String>, CrescaError> {
+    if let Some(author) = author {
+        validate_author(author)?;
+    }
+
+    // Staged changes would end up in the rewritten approve commit
+    let has_staged_changes = run_git_command(
+        git,
+        "check staged changes",
+        &["diff", "--cached", "--quiet"],
+        verbose,
+    )
+    .is_err();
+    if has_staged_changes {
+        return Err(CrescaError::StagedChanges);
+    }
+
+    let (approved_count, _) = count_approvals(git, from_branch, verbose)?;
+    if approved_count == 0 {
+        return Err(CrescaError::NotInLatestApproval(paths.to_vec()));
+    }
+
+    let mut files = Vec::new();
+    let mut not_approved = Vec::new();
+    for path in paths {
+        let output = run_git_command(
+            git,
+            &format!("get the files of {} in the most recent approval", path),
+            &["diff", "--name-only", "-z", "HEAD^", "HEAD", "--", path],
+            verbose,
+        )?;
+        let changed: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|file| !file.is_empty())
+            .map(str::to_string)
+            .collect();
+        if changed.is_empty() {
+            not_approved.push(path.clone());
+        }
+        files.extend(changed);
+    }
+    if !not_approved.is_empty() {
+        return Err(CrescaError::NotInLatestApproval(not_approved));
+    }
+    files.sort();
+    files.dedup();
+
+    // Take the files out of the approval in the index only, so their approved content stays in the working tree
+    let pathspecs: Vec<String> = files
+        .iter()
+        .map(|file| format!(":(top,literal){}", file))
+        .collect();
+    let mut args = vec!["restore", "--source=HEAD^", "--staged", "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    run_git_command(git, "take the files out of the approval", &args, verbose)?;
+
+    let approval_left = run_git_command(
+        git,
+        "check whether the approval has other changes",
+        &["diff", "--cached", "--quiet", "HEAD^"],
+        verbose,
+    )
+    .is_err();
+    let message = String::from_utf8_lossy(
+        &run_git_command(
+            git,
+            "get the message of the most recent approval",
+            &["log", "-1", "--format=%B", "HEAD"],
+            verbose,
+        )?
+        .stdout,
+    )
+    .trim_end()
+    .to_string();
+    let approval = rev_parse(git, "HEAD", verbose)?;
+    run_git_command(
+        git,
+        "drop the most recent approval",
+        &["reset", "--soft", "--quiet", "HEAD^"],
+        verbose,
+    )?;
+    if approval_left {
+        let flags = CommitFlags {
+            sign,
+            no_verify,
+            allow_empty: false,
+            author,
+        };
+        let recreated = commit(
+            git,
+            "recreate the most recent approval",
+            &message,
+            flags,
+            false,
+            verbose,
+        );
+        // Put the approval back as it was if e.g. a hook rejects the commit
+        if let Err(e) = recreated {
+            run_git_command(
+                git,
+                "restore the most recent approval",
+                &["reset", "--soft", "--quiet", &approval],
+                verbose,
+            )?;
+            run_git_command(
+                git,
+                "unstage unapproved changes",
+                &["reset", "--quiet"],
+                verbose,
+            )?;
+            return Err(e);
+        }
+    }
+    // New files become untracked again, like before they were approved
+    run_git_command(
+        git,
+        "unstage unapproved changes",
+        &["reset", "--quiet"],
+        verbose,
+    )?;
+
+    Ok(files)
+}
+
+/// Review log information
+pub struct ReviewLog {
+    pub approved: Vec<String>,
+    pub remaining: Vec<String>,
+}
+
+/// Get review log (approved commits on the review branch and commits of the development branch)
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `ReviewLog` - The one-line logs of approved and remaining commits
+pub fn get_review_log(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<ReviewLog, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    let merge_base = review_merge_base(git, &review_branch, to_branch, from_branch, verbose)?;
+
+    let oneline_log = |description: &str, range: String| -> Result<Vec<String>, CrescaError> {
+        let output = run_git_command(git, description, &["log", "--oneline", &range], verbose)?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect())
+    };
+
+    Ok(ReviewLog {
+        approved: oneline_log("get approved commits", format!("{}..HEAD", merge_base))?,
+        remaining: oneline_log(
+            "get remaining commits",
+            format!("{}..{}", merge_base, from_branch),
+        )?,
+    })
+}
+
+/// Stash uncommitted changes, including untracked files
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `message` - The stash message.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn stash_changes(
+    git: &impl GitRunner,
+    message: &str,
+    dry_run: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    run_mutating_git_command(
+        git,
+        "stash uncommitted changes",
+        &[
+            "stash",
+            "push",
+            "--include-untracked",
+            "--quiet",
+            "-m",
+            message,
+        ],
+        dry_run,
+        verbose,
+    )?;
+    Ok(())
+}
+
+/// Get the approved commits on the review branch as a patch series
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `String` - The output of `git format-patch`, which can be applied with `git am`
+pub fn get_review_patch(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<String, CrescaError> {
+    let review_branch = get_current_branch(git, verbose)?;
+    let merge_base = review_merge_base(git, &review_branch, to_branch, from_branch, verbose)?;
+    let output = run_git_command(
+        git,
+        "export approved changes",
+        &["format-patch", "--stdout", &format!("{}..HEAD", merge_base)],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Write the remaining diff between the review branch and the development branch
+///
+/// The diff is streamed from git as it is produced, so large diffs are not held in memory. Files
+/// excluded from the review are left out, like in `get_review_status`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from_branch` - The development branch to compare against.
+/// * `paths` - Paths to limit the diff to. All files are included if empty.
+/// * `stat` - Whether to show the diff stat instead of the full diff.
+/// * `out` - The writer to write the output of `git diff` to.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn write_review_diff(
+    git: &impl GitRunner,
+    from_branch: &str,
+    paths: &[String],
+    stat: bool,
+    out: &mut dyn Write,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    let color = if colored::control::SHOULD_COLORIZE.should_colorize() {
+        "--color=always"
+    } else {
+        "--color=never"
+    };
+    let mut args = vec!["diff", color];
+    if stat {
+        args.push("--stat");
+    }
+    let scope = ReviewScope::of(git, &get_current_branch(git, verbose)?, verbose)?;
+    if scope.ignore_eol {
+        args.push("--ignore-cr-at-eol");
+    }
+    let excluded = scope.excluded_pathspecs(git, "HEAD", from_branch, verbose)?;
+    args.extend(["HEAD", from_branch, "--"]);
+    args.extend(paths.iter().map(|path| path.as_str()));
+    args.extend(excluded.iter().map(String::as_str));
+
+    run_streaming_git_command(git, "get remaining diff", &args, out, verbose)?;
+    Ok(())
+}
+
+/// Review branch information
+pub struct ReviewBranch {
+    pub name: String,
+    pub to_branch: String,
+    pub from_branch: String,
+    /// The head branch of the reviewed GitHub pull request, whose head is fetched into `from_branch`.
+    pub pull_request_head: Option<String>,
+    pub last_commit_date: String,
+    pub is_current: bool,
+}
+
+/// List all review branches
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `prefix` - The prefix of review branch names.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<ReviewBranch>` - The review branches
+pub fn list_review_branches(
+    git: &impl GitRunner,
+    prefix: &str,
+    verbose: u8,
+) -> Result<Vec<ReviewBranch>, CrescaError> {
+    let output = run_git_command(
+        git,
+        "list review branches",
+        &[
+            "branch",
+            "--list",
+            &format!("{}-*", prefix),
+            "--format=%(HEAD)%09%(refname:short)%09%(committerdate:relative)",
+        ],
+        verbose,
+    )?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let is_current = fields.next()? == "*";
+            let name = fields.next()?.to_string();
+            let last_commit_date = fields.next()?.to_string();
+            let (to_branch, from_branch) = get_branch_review_info(git, prefix, &name, verbose)?;
+            let pull_request_head =
+                get_config(git, &format!("cresca.{}.pullRequestHead", name), verbose);
+            Some(ReviewBranch {
+                name,
+                to_branch,
+                from_branch,
+                pull_request_head,
+                last_commit_date,
+                is_current,
+            })
+        })
+        .collect())
+}
+
+/// Result of cleaning review branches
+pub struct CleanResult {
+    /// Review branches that were deleted.
+    pub deleted: Vec<String>,
+    /// Review branches that were skipped because unapproved changes remain.
+    pub skipped: Vec<String>,
+}
+
+/// Delete review branches whose development branch has been merged or whose branches no longer exist
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `prefix` - The prefix of review branch names.
+/// * `force` - Whether to delete review branches even if unapproved changes remain.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `CleanResult` - The deleted and skipped review branches
+pub fn clean_review_branches(
+    git: &impl GitRunner,
+    prefix: &str,
+    force: bool,
+    verbose: u8,
+) -> Result<CleanResult, CrescaError> {
+    let mut result = CleanResult {
+        deleted: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    for branch in list_review_branches(git, prefix, verbose)? {
+        // The current branch cannot be deleted
+        if branch.is_current {
+            continue;
+        }
+
+        // The branches may also be tags or commits
+        let branches_exist = rev_parse(git, &branch.to_branch, verbose).is_ok()
+            && rev_parse(git, &branch.from_branch, verbose).is_ok();
+        if branches_exist {
+            let is_merged = run_git_command(
+                git,
+                "check whether the development branch is merged",
+                &[
+                    "merge-base",
+                    "--is-ancestor",
+                    &branch.from_branch,
+                    &branch.to_branch,
+                ],
+                verbose,
+            )
+            .is_ok();
+            if !is_merged {
+                continue;
+            }
+
+            let has_remaining_diff = run_git_command(
+                git,
+                "check remaining diff",
+                &["diff", "--quiet", &branch.name, &branch.from_branch],
+                verbose,
+            )
+            .is_err();
+            if has_remaining_diff && !force {
+                result.skipped.push(branch.name);
+                continue;
+            }
+        }
+
+        delete_review_branch(git, &branch.name, verbose)?;
+        result.deleted.push(branch.name);
+    }
+
+    Ok(result)
 }
@@ -0,0 +1,156 @@
+use crate::error::CrescaError;
+use crate::git::{get_config, run_git_command, GitRunner};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Name of the repository config file
+const REPO_CONFIG_FILE: &str = ".cresca.toml";
+
+/// The git config keys of cresca settings and their descriptions
+pub const GIT_CONFIG_KEYS: &[(&str, &str)] = &[
+    (
+        "cresca.defaultTo",
+        "The target branch used when `cresca review` is given only the development branch",
+    ),
+    ("cresca.remote", "The remote to pull the branches from"),
+    ("cresca.prefix", "The prefix of review branch names"),
+    (
+        "cresca.approveMessage",
+        "The default commit message of `cresca approve`",
+    ),
+    (
+        "cresca.worktreeDir",
+        "The directory to create the worktrees of `cresca review --worktree` in",
+    ),
+    ("cresca.sign", "Whether to sign the approve commits"),
+    (
+        "cresca.autoApprove",
+        "A glob of files to approve automatically when preparing a review branch (can be added multiple times)",
+    ),
+    (
+        "cresca.exclude",
+        "A glob of files to leave out of reviews without approving them (can be added multiple times)",
+    ),
+];
+
+/// cresca configuration loaded from `.cresca.toml` and the global config file
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The default branch where PRs are planned to be merged into.
+    pub default_to: Option<String>,
+    /// The remote to pull the branches from.
+    pub remote: Option<String>,
+    /// The prefix of review branch names.
+    pub prefix: Option<String>,
+    /// The default commit message for approved changes.
+    pub approve_message: Option<String>,
+    /// The directory to create review worktrees in, relative to the repository root.
+    pub worktree_dir: Option<String>,
+}
+
+impl Config {
+    /// Fill unset fields with the values of another config
+    fn or(self, other: Config) -> Config {
+        Config {
+            default_to: self.default_to.or(other.default_to),
+            remote: self.remote.or(other.remote),
+            prefix: self.prefix.or(other.prefix),
+            approve_message: self.approve_message.or(other.approve_message),
+            worktree_dir: self.worktree_dir.or(other.worktree_dir),
+        }
+    }
+}
+
+/// Load the configuration
+///
+/// Values in the `cresca.*` git config take precedence over `.cresca.toml` at the repository root, which
+/// takes precedence over the global config file (`$XDG_CONFIG_HOME/cresca/config.toml`, or
+/// `~/.config/cresca/config.toml`).
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(Config)` - The merged configuration
+/// * `Err(CrescaError)` - If a config file could not be parsed
+pub fn load_config(git: &impl GitRunner, verbose: u8) -> Result<Config, CrescaError> {
+    let repo_config = get_repo_root(git, verbose)
+        .map(|root| read_config_file(&root.join(REPO_CONFIG_FILE)))
+        .transpose()?
+        .unwrap_or_default();
+    let global_config = get_global_config_path()
+        .map(|path| read_config_file(&path))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(read_git_config(git, verbose)
+        .or(repo_config)
+        .or(global_config))
+}
+
+/// Resolve a cresca git config key, with or without the `cresca.` prefix
+///
+/// # Arguments
+///
+/// * `key` - The key to resolve (e.g. `remote` or `cresca.remote`).
+///
+/// # Returns
+///
+/// * `Ok(&str)` - The full key
+/// * `Err(CrescaError)` - If the key is not a known cresca setting
+pub fn resolve_git_config_key(key: &str) -> Result<&'static str, CrescaError> {
+    let name = key.strip_prefix("cresca.").unwrap_or(key);
+    GIT_CONFIG_KEYS
+        .iter()
+        .map(|(known_key, _)| *known_key)
+        .find(|known_key| known_key.eq_ignore_ascii_case(&format!("cresca.{}", name)))
+        .ok_or_else(|| CrescaError::UnknownConfigKey(key.to_string()))
+}
+
+/// Read the settings in the `cresca.*` git config
+fn read_git_config(git: &impl GitRunner, verbose: u8) -> Config {
+    Config {
+        default_to: get_config(git, "cresca.defaultTo", verbose),
+        remote: get_config(git, "cresca.remote", verbose),
+        prefix: get_config(git, "cresca.prefix", verbose),
+        approve_message: get_config(git, "cresca.approveMessage", verbose),
+        worktree_dir: get_config(git, "cresca.worktreeDir", verbose),
+    }
+}
+
+/// Get the root directory of the repository
+fn get_repo_root(git: &impl GitRunner, verbose: u8) -> Option<PathBuf> {
+    let output = run_git_command(
+        git,
+        "get repository root",
+        &["rev-parse", "--show-toplevel"],
+        verbose,
+    )
+    .ok()?;
+    Some(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Get the path of the global config file
+fn get_global_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("cresca").join("config.toml"))
+}
+
+/// Read a config file, returning the default config if it does not exist
+fn read_config_file(path: &Path) -> Result<Config, CrescaError> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(Config::default());
+    };
+    toml::from_str(&content).map_err(|e| CrescaError::InvalidConfig {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
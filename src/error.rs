@@ -0,0 +1,390 @@
+use crate::config::GIT_CONFIG_KEYS;
+use crate::git::{git_binary, GitError};
+use crate::messages::{fill, msg, Id};
+use colored::Colorize;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Error returned by the cresca commands
+#[derive(Debug)]
+pub enum CrescaError {
+    /// A git command failed.
+    Git(GitError),
+    /// A git command failed to sign a commit.
+    SigningFailed(GitError),
+    /// The git executable was not found.
+    GitNotFound(String),
+    /// The current directory is not inside a git repository.
+    NotGitRepository,
+    /// The current branch is not a review branch.
+    NotReviewBranch,
+    /// The given branch is not a review branch.
+    UnknownReviewBranch(String),
+    /// No review branch exists for the given branches.
+    NoReviewBranch {
+        to_branch: String,
+        from_branch: String,
+    },
+    /// HEAD is detached, so there is no current branch.
+    DetachedHead,
+    /// The working tree has uncommitted changes.
+    DirtyWorkingTree,
+    /// The config file could not be parsed.
+    InvalidConfig { path: PathBuf, message: String },
+    /// The key is not a known cresca setting.
+    UnknownConfigKey(String),
+    /// The remote does not exist.
+    RemoteNotFound(String),
+    /// The target branch and the development branch are the same.
+    SameBranch(String),
+    /// The target branch and the development branch point to the same commit.
+    SameCommit {
+        to_branch: String,
+        from_branch: String,
+    },
+    /// The target branch and the development branch have no common ancestor.
+    UnrelatedHistories {
+        to_branch: String,
+        from_branch: String,
+    },
+    /// The commit is not in the commit range of the review.
+    CommitNotInRange { commit: String, range: String },
+    /// The short hash matches more than one commit in the commit range of the review.
+    AmbiguousCommit {
+        commit: String,
+        matches: Vec<String>,
+    },
+    /// The `--skip-to` commit is a merge commit.
+    SkipToMergeCommit(String),
+    /// The author to override the git identity with is not in the `Name <email>` format.
+    InvalidAuthor(String),
+    /// A network-bound git command did not finish within `--timeout`.
+    GitTimeout {
+        description: String,
+        timeout: Duration,
+    },
+    /// The development branch was rewritten, so commits reviewed before are no longer in it.
+    ReviewedCommitsDisappeared {
+        branch: String,
+        commits: Vec<String>,
+    },
+    /// The `--stop-at` commit is before the `--skip-to` commit.
+    StopAtBeforeSkipTo { stop_at: String, skip_to: String },
+    /// A squash merge stopped on conflicts, which are left in the working tree.
+    MergeConflicts {
+        description: String,
+        files: Vec<String>,
+    },
+    /// No squash merge stopped on conflicts on the review branch.
+    NothingToContinue,
+    /// Conflicted files are not resolved yet.
+    UnresolvedConflicts(Vec<String>),
+    /// The branch to switch back to no longer exists and the default branch is unknown.
+    BranchNotFound(String),
+    /// The given revision does not resolve to a commit.
+    NotACommit(String),
+    /// The `--range` argument is not in the `<base>..<head>` format.
+    InvalidRange(String),
+    /// An end of the `--range` argument is not a commit.
+    RangeNotACommit(String),
+    /// Only one branch is given and `default_to` is not set.
+    MissingFromBranch,
+    /// The development branch of the review no longer exists.
+    FromBranchGone(String),
+    /// The review has no approved changes to export.
+    NothingToExport,
+    /// The exported patch could not be written.
+    WriteFailed { path: String, message: String },
+    /// The error was already printed in the requested output format, e.g. as JSON for `status --json`.
+    AlreadyReported,
+    /// No approval can be undone. `auto_approved` is whether only the auto-approve commit is left.
+    NothingToUndo { auto_approved: bool },
+    /// There are staged changes that would be mixed into a rewritten approve commit.
+    StagedChanges,
+    /// The paths were not changed by the most recent approve commit.
+    NotInLatestApproval(Vec<String>),
+    /// A glob of the files to auto-approve is invalid.
+    InvalidGlob { glob: String, message: String },
+    /// The `--format` template of the status is invalid.
+    InvalidFormat(String),
+    /// Unreviewed changes remain in this number of files.
+    UnreviewedChanges(usize),
+    /// The review worktree could not be entered.
+    Worktree { path: PathBuf, message: String },
+    /// Preparing the review branch was interrupted, e.g. with Ctrl-C.
+    Interrupted,
+    /// Preparing the review branch was interrupted, and the repository was restored to the given branch.
+    ReviewInterrupted(String),
+    /// The Ctrl-C handler could not be installed.
+    SignalHandler(String),
+    /// The log file of the git commands could not be opened.
+    LogFile { path: PathBuf, message: String },
+    /// The GitHub CLI (`gh`) is not installed.
+    GitHubCliNotFound,
+    /// The GitHub CLI (`gh`) failed.
+    GitHub { description: String, detail: String },
+}
+
+impl fmt::Display for CrescaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrescaError::Git(e) => write!(f, "{}", e),
+            CrescaError::SigningFailed(e) => write!(
+                f,
+                "{}",
+                fill(Id::SigningFailed, &[e, &"cresca.sign=false".green()])
+            ),
+            CrescaError::GitNotFound(git) => write!(
+                f,
+                "{}",
+                fill(Id::GitNotFound, &[git, &"CRESCA_GIT".green()])
+            ),
+            CrescaError::NotGitRepository => write!(f, "{}", msg(Id::NotGitRepository)),
+            CrescaError::NotReviewBranch => write!(
+                f,
+                "{}",
+                fill(Id::NotReviewBranch, &[&"cresca review".green()])
+            ),
+            CrescaError::UnknownReviewBranch(branch) => write!(
+                f,
+                "{}",
+                fill(Id::UnknownReviewBranch, &[branch, &"cresca list".green()])
+            ),
+            CrescaError::NoReviewBranch {
+                to_branch,
+                from_branch,
+            } => write!(
+                f,
+                "{}",
+                fill(
+                    Id::NoReviewBranch,
+                    &[
+                        to_branch,
+                        from_branch,
+                        &format!("cresca review {} {}", to_branch, from_branch).green()
+                    ]
+                )
+            ),
+            CrescaError::DetachedHead => write!(f, "{}", msg(Id::DetachedHead)),
+            CrescaError::DirtyWorkingTree => write!(
+                f,
+                "{}",
+                fill(Id::DirtyWorkingTree, &[&"--autostash".green()])
+            ),
+            CrescaError::InvalidConfig { path, message } => write!(
+                f,
+                "{}",
+                fill(Id::InvalidConfig, &[&path.display(), message])
+            ),
+            CrescaError::UnknownConfigKey(key) => {
+                write!(f, "{}", fill(Id::UnknownConfigKey, &[key]))?;
+                for (known_key, description) in GIT_CONFIG_KEYS {
+                    write!(f, "\n    - {}: {}", known_key, description)?;
+                }
+                Ok(())
+            }
+            CrescaError::RemoteNotFound(remote) => write!(
+                f,
+                "{}",
+                fill(Id::RemoteNotFound, &[remote, &"--remote".green()])
+            ),
+            CrescaError::SameBranch(branch) => write!(f, "{}", fill(Id::SameBranch, &[branch])),
+            CrescaError::SameCommit {
+                to_branch,
+                from_branch,
+            } => write!(f, "{}", fill(Id::SameCommit, &[to_branch, from_branch])),
+            CrescaError::UnrelatedHistories {
+                to_branch,
+                from_branch,
+            } => write!(
+                f,
+                "{}",
+                fill(
+                    Id::UnrelatedHistories,
+                    &[to_branch, from_branch, &"--allow-unrelated".green()]
+                )
+            ),
+            CrescaError::CommitNotInRange { commit, range } => {
+                write!(f, "{}", fill(Id::CommitNotInRange, &[commit, range]))
+            }
+            CrescaError::AmbiguousCommit { commit, matches } => {
+                write!(
+                    f,
+                    "{}",
+                    fill(Id::AmbiguousCommit, &[commit, &matches.len()])
+                )?;
+                for hash in matches {
+                    write!(f, "\n    - {}", hash)?;
+                }
+                Ok(())
+            }
+            CrescaError::SkipToMergeCommit(commit) => write!(
+                f,
+                "{}",
+                fill(Id::SkipToMergeCommit, &[commit, &"--skip-to".green()])
+            ),
+            CrescaError::InvalidAuthor(author) => {
+                write!(f, "{}", fill(Id::InvalidAuthor, &[author]))
+            }
+            CrescaError::GitTimeout {
+                description,
+                timeout,
+            } => write!(
+                f,
+                "{}",
+                fill(
+                    Id::GitTimeout,
+                    &[description, &timeout.as_secs(), &"--timeout".green()]
+                )
+            ),
+            CrescaError::ReviewedCommitsDisappeared { branch, commits } => {
+                write!(f, "{}", fill(Id::ReviewedCommitsDisappeared, &[branch]))?;
+                for commit in commits {
+                    write!(f, "\n    - {}", commit)?;
+                }
+                write!(
+                    f,
+                    "\n{}",
+                    fill(
+                        Id::ReviewedCommitsDisappearedHint,
+                        &[&"cresca review --force".green()]
+                    )
+                )
+            }
+            CrescaError::StopAtBeforeSkipTo { stop_at, skip_to } => {
+                write!(f, "{}", fill(Id::StopAtBeforeSkipTo, &[stop_at, skip_to]))
+            }
+            CrescaError::MergeConflicts { description, files } => {
+                write!(f, "{}", fill(Id::MergeConflicts, &[description]))?;
+                for file in files {
+                    write!(f, "\n    - {}", file)?;
+                }
+                write!(
+                    f,
+                    "\n{}",
+                    fill(
+                        Id::MergeConflictsHint,
+                        &[&"cresca continue".green(), &"cresca abort".green()]
+                    )
+                )
+            }
+            CrescaError::NothingToContinue => write!(f, "{}", msg(Id::NothingToContinue)),
+            CrescaError::UnresolvedConflicts(files) => {
+                write!(f, "{}", msg(Id::UnresolvedConflicts))?;
+                for file in files {
+                    write!(f, "\n    - {}", file)?;
+                }
+                Ok(())
+            }
+            CrescaError::BranchNotFound(branch) => {
+                write!(f, "{}", fill(Id::BranchNotFound, &[branch]))
+            }
+            CrescaError::NotACommit(revision) => {
+                write!(f, "{}", fill(Id::RevisionNotACommit, &[revision]))
+            }
+            CrescaError::InvalidRange(range) => write!(f, "{}", fill(Id::InvalidRange, &[range])),
+            CrescaError::RangeNotACommit(revision) => {
+                write!(f, "{}", fill(Id::NotACommit, &[revision]))
+            }
+            CrescaError::MissingFromBranch => write!(
+                f,
+                "{}",
+                fill(Id::MissingFromBranch, &[&"default_to".green()])
+            ),
+            CrescaError::FromBranchGone(branch) => write!(
+                f,
+                "{}",
+                fill(
+                    Id::FromBranchGone,
+                    &[
+                        branch,
+                        &"cresca abort".green(),
+                        &"cresca review <TO> <FROM>".green()
+                    ]
+                )
+            ),
+            CrescaError::NothingToExport => write!(f, "{}", msg(Id::NothingToExport)),
+            CrescaError::WriteFailed { path, message } => {
+                write!(f, "{}", fill(Id::WriteFailed, &[path, message]))
+            }
+            CrescaError::AlreadyReported => Ok(()),
+            CrescaError::StagedChanges => write!(
+                f,
+                "{}",
+                fill(
+                    Id::StagedChanges,
+                    &[&"cresca approve".green(), &"cresca reset".green()]
+                )
+            ),
+            CrescaError::NotInLatestApproval(paths) => {
+                write!(f, "{}", msg(Id::NotInLatestApproval))?;
+                for path in paths {
+                    write!(f, "\n    - {}", path)?;
+                }
+                write!(
+                    f,
+                    "\n{}",
+                    fill(Id::NotInLatestApprovalHint, &[&"cresca undo".green()])
+                )
+            }
+            CrescaError::NothingToUndo {
+                auto_approved: true,
+            } => write!(
+                f,
+                "{}",
+                fill(Id::NothingToUndoAutoApproved, &[&"--skip-to".green()])
+            ),
+            CrescaError::NothingToUndo {
+                auto_approved: false,
+            } => write!(f, "{}", msg(Id::NothingToUndo)),
+            CrescaError::InvalidGlob { glob, message } => {
+                write!(f, "{}", fill(Id::InvalidGlob, &[glob, message]))
+            }
+            CrescaError::InvalidFormat(message) => {
+                write!(f, "{}", fill(Id::InvalidFormat, &[message]))
+            }
+            CrescaError::UnreviewedChanges(file_count) => write!(
+                f,
+                "{}",
+                fill(Id::UnreviewedChanges, &[file_count, &"--partial".green()])
+            ),
+            CrescaError::Worktree { path, message } => {
+                write!(f, "{}", fill(Id::Worktree, &[&path.display(), message]))
+            }
+            CrescaError::Interrupted => write!(f, "{}", msg(Id::Interrupted)),
+            CrescaError::ReviewInterrupted(branch) => {
+                write!(f, "{}", fill(Id::ReviewInterrupted, &[&branch.green()]))
+            }
+            CrescaError::SignalHandler(message) => {
+                write!(f, "{}", fill(Id::SignalHandler, &[message]))
+            }
+            CrescaError::LogFile { path, message } => {
+                write!(f, "{}", fill(Id::LogFile, &[&path.display(), message]))
+            }
+            CrescaError::GitHubCliNotFound => write!(f, "{}", msg(Id::GitHubCliNotFound)),
+            CrescaError::GitHub {
+                description,
+                detail,
+            } => write!(f, "{}", fill(Id::GitHub, &[description, detail])),
+        }
+    }
+}
+
+impl std::error::Error for CrescaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CrescaError::Git(e) | CrescaError::SigningFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<GitError> for CrescaError {
+    fn from(e: GitError) -> Self {
+        if e.not_found {
+            return CrescaError::GitNotFound(git_binary().to_string());
+        }
+        CrescaError::Git(e)
+    }
+}
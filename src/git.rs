@@ -1,46 +1,669 @@
+use crate::error::CrescaError;
 use colored::Colorize;
-use std::process::{exit, Command, Output};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// The read-only operations are implemented with libgit2 instead of running git
+#[cfg(feature = "libgit2")]
+pub use crate::libgit2::{get_current_branch, get_file_stats, get_merge_base, rev_list};
+
+/// Error returned when a git command fails
+#[derive(Debug)]
+pub struct GitError {
+    /// The description of the git command.
+    pub description: String,
+    /// The arguments passed to the git command.
+    pub args: Vec<String>,
+    /// The stderr of the git command, or the reason why git could not be run.
+    pub stderr: String,
+    /// Whether the git executable was not found, so the command did not run.
+    pub not_found: bool,
+    /// The exit code of git, or `None` if it could not be run or was killed by a signal.
+    pub code: Option<i32>,
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to {}.", self.description)
+    }
+}
+
+impl std::error::Error for GitError {}
+
+/// The git executable set by `set_git_binary`
+static GIT_BINARY: OnceLock<String> = OnceLock::new();
+
+/// Set the git executable used for all git commands
+///
+/// Only the first call takes effect. Defaults to `git` on `PATH` if never called.
+///
+/// # Arguments
+///
+/// * `path` - The path or name of the git executable.
+pub fn set_git_binary(path: &str) {
+    let _ = GIT_BINARY.set(path.to_string());
+}
+
+/// Get the git executable used for all git commands
+pub(crate) fn git_binary() -> &'static str {
+    GIT_BINARY.get().map(String::as_str).unwrap_or("git")
+}
+
+/// The directory set by `set_git_work_dir`
+static GIT_WORK_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Run the git commands from now on in another directory, like `git -C <dir>`
+///
+/// The current directory of the process is left as it is.
+///
+/// # Arguments
+///
+/// * `dir` - The directory to run git in, or `None` to go back to the current directory.
+pub fn set_git_work_dir(dir: Option<&Path>) {
+    *GIT_WORK_DIR.write().unwrap_or_else(PoisonError::into_inner) = dir.map(Path::to_path_buf);
+}
+
+/// Get the directory set by `set_git_work_dir`, if any
+pub(crate) fn git_work_dir() -> Option<PathBuf> {
+    GIT_WORK_DIR
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Create a command running the git executable, in the directory set by `set_git_work_dir` if any
+fn git_command() -> Command {
+    let mut command = Command::new(git_binary());
+    if let Some(dir) = git_work_dir() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// The log file set by `set_log_file`
+static LOG_FILE: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// The maximum length of the stderr of a failed git command in the log file
+const LOGGED_STDERR_LIMIT: usize = 1000;
+
+/// Record every git command run from now on in a log file
+///
+/// Each time the git executable is run, a line with the time, the arguments and the exit status is
+/// appended to the file, followed by the stderr of git (truncated) if it failed. Every entry is
+/// written at once and flushed, so several invocations of cresca can share the file.
+/// Only the first call takes effect. Commands answered by a runner other than `ProcessGitRunner` are not logged.
+///
+/// # Arguments
+///
+/// * `path` - The path of the log file. Created if it does not exist, and appended to otherwise.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the log file was opened.
+/// * `Err(io::Error)` - If the log file could not be opened.
+pub fn set_log_file(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let _ = LOG_FILE.set(Mutex::new(file));
+    Ok(())
+}
+
+/// Append a git command to the log file set by `set_log_file`, if any
+///
+/// # Arguments
+///
+/// * `args` - The arguments passed to the git command.
+/// * `status` - The exit status of git, or `None` if it could not be run.
+/// * `stderr` - The stderr of git, or the reason why it could not be run. Only logged if it failed.
+fn log_git_command(args: &[&str], status: Option<ExitStatus>, stderr: &str) {
+    let Some(file) = LOG_FILE.get() else {
+        return;
+    };
+    let outcome = match status.map(|status| status.code()) {
+        Some(Some(code)) => format!("exit {}", code),
+        Some(None) => "killed by a signal".to_string(),
+        None => "not run".to_string(),
+    };
+    let mut entry = format!(
+        "{} git {} ({})\n",
+        format_utc(SystemTime::now()),
+        args.join(" "),
+        outcome
+    );
+    if !status.is_some_and(|status| status.success()) {
+        let stderr = stderr.trim_end();
+        let truncated = match stderr.char_indices().nth(LOGGED_STDERR_LIMIT) {
+            Some((end, _)) => format!("{}...", &stderr[..end]),
+            None => stderr.to_string(),
+        };
+        for line in truncated.lines() {
+            entry.push_str(&format!("    {}\n", line));
+        }
+    }
+
+    // A failing log file must not fail the git command, so write errors are ignored
+    let mut file = file.lock().unwrap_or_else(PoisonError::into_inner);
+    let _ = file.write_all(entry.as_bytes());
+    let _ = file.flush();
+}
+
+/// Format a time in UTC as ISO 8601, e.g. `2024-05-01T12:34:56Z`
+///
+/// # Arguments
+///
+/// * `time` - The time to format.
+fn format_utc(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // Convert the days since 1970-01-01 to a date, counting years from March so leap days come last
+    let days = days + 719468;
+    let (era, day_of_era) = (days / 146097, days % 146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Runner of git commands
+///
+/// Every command of cresca runs git through the runner passed down by its caller, which is
+/// `ProcessGitRunner` outside of tests. Another runner can stand in for git, e.g. to check which git
+/// commands are run without a repository. Only `run` has to be implemented, as the streamed, timed
+/// and interactive variants fall back to it.
+pub trait GitRunner: Send + Sync {
+    /// Run a git command and return the output
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description of the git command.
+    /// * `args` - The arguments to pass to the git command.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Output)` - The output of the git command if it succeeded.
+    /// * `Err(GitError)` - If git could not be run or exited with a failure status.
+    fn run(&self, description: &str, args: &[&str]) -> Result<Output, GitError>;
+
+    /// Run a git command and copy its output to a writer
+    ///
+    /// A closed writer (e.g. a pager that quit) is not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description of the git command.
+    /// * `args` - The arguments to pass to the git command.
+    /// * `out` - The writer to copy the output of git to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the git command succeeded or the writer was closed.
+    /// * `Err(GitError)` - If git could not be run or exited with a failure status.
+    fn run_streaming(
+        &self,
+        description: &str,
+        args: &[&str],
+        out: &mut dyn Write,
+    ) -> Result<(), GitError> {
+        let output = self.run(description, args)?;
+        match out.write_all(&output.stdout).and_then(|_| out.flush()) {
+            Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(GitError {
+                description: description.to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                stderr: e.to_string(),
+                not_found: false,
+                code: None,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run a git command and return the output, giving up after a timeout
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description of the git command.
+    /// * `args` - The arguments to pass to the git command.
+    /// * `_timeout` - How long to let git run.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Output)` - The output of the git command if it succeeded in time.
+    /// * `Err(CrescaError::GitTimeout)` - If git was stopped after the timeout.
+    /// * `Err(CrescaError::Git)` - If git could not be run or exited with a failure status.
+    fn run_with_timeout(
+        &self,
+        description: &str,
+        args: &[&str],
+        _timeout: Duration,
+    ) -> Result<Output, CrescaError> {
+        Ok(self.run(description, args)?)
+    }
+
+    /// Run an interactive git command attached to the terminal
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description of the git command.
+    /// * `args` - The arguments to pass to the git command.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the git command succeeded.
+    /// * `Err(GitError)` - If git could not be run or exited with a failure status.
+    fn run_interactive(&self, description: &str, args: &[&str]) -> Result<(), GitError> {
+        self.run(description, args).map(|_| ())
+    }
+}
+
+/// Runner spawning the git executable set by `set_git_binary`
+pub struct ProcessGitRunner;
+
+impl GitRunner for ProcessGitRunner {
+    fn run(&self, description: &str, args: &[&str]) -> Result<Output, GitError> {
+        let error = |stderr: String, code: Option<i32>| GitError {
+            description: description.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            stderr,
+            not_found: false,
+            code,
+        };
+        // Parse git output in a known locale regardless of the user's environment
+        let output = git_command()
+            .args(args)
+            .env("LANG", "C")
+            .env("LC_ALL", "C")
+            .output()
+            .map_err(|e| spawn_error(description, args, e))?;
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        log_git_command(args, Some(output.status), &stderr);
+        if !output.status.success() {
+            return Err(error(stderr, output.status.code()));
+        }
+        Ok(output)
+    }
+
+    fn run_streaming(
+        &self,
+        description: &str,
+        args: &[&str],
+        out: &mut dyn Write,
+    ) -> Result<(), GitError> {
+        let error = |stderr: String, code: Option<i32>| GitError {
+            description: description.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            stderr,
+            not_found: false,
+            code,
+        };
+        let mut child = git_command()
+            .args(args)
+            .env("LANG", "C")
+            .env("LC_ALL", "C")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(description, args, e))?;
+
+        // Drain stderr concurrently so git never blocks on a full stderr pipe
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = thread::spawn(move || {
+            let mut buffer = String::new();
+            stderr.read_to_string(&mut buffer).ok();
+            buffer
+        });
+
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let copied = io::copy(&mut stdout, out).and_then(|_| out.flush());
+        if let Err(e) = copied {
+            let _ = child.kill();
+            let status = child.wait().ok();
+            log_git_command(args, status, &e.to_string());
+            if e.kind() == ErrorKind::BrokenPipe {
+                return Ok(());
+            }
+            return Err(error(e.to_string(), None));
+        }
+        let status = child.wait().map_err(|e| error(e.to_string(), None))?;
+        let stderr = stderr_reader.join().unwrap_or_default();
+        log_git_command(args, Some(status), &stderr);
+        if !status.success() {
+            return Err(error(stderr, status.code()));
+        }
+        Ok(())
+    }
+
+    fn run_with_timeout(
+        &self,
+        description: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<Output, CrescaError> {
+        let error = |stderr: String, code: Option<i32>| GitError {
+            description: description.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            stderr,
+            not_found: false,
+            code,
+        };
+        let start = Instant::now();
+        let mut child = git_command()
+            .args(args)
+            .env("LANG", "C")
+            .env("LC_ALL", "C")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(description, args, e))?;
+
+        // Drain both pipes concurrently so git never blocks on a full pipe while it is waited for
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let stdout_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            stdout.read_to_end(&mut buffer).ok();
+            buffer
+        });
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            stderr.read_to_end(&mut buffer).ok();
+            buffer
+        });
+
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|e| error(e.to_string(), None))? {
+                break status;
+            }
+            if start.elapsed() >= timeout {
+                let _ = child.kill();
+                let status = child.wait().ok();
+                log_git_command(args, status, &format!("timed out after {:?}", timeout));
+                // The readers are left behind, as processes spawned by git may still hold the pipes
+                return Err(CrescaError::GitTimeout {
+                    description: description.to_string(),
+                    timeout,
+                });
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+        let output = Output {
+            status,
+            stdout: stdout_reader.join().unwrap_or_default(),
+            stderr: stderr_reader.join().unwrap_or_default(),
+        };
+        log_git_command(
+            args,
+            Some(output.status),
+            &String::from_utf8_lossy(&output.stderr),
+        );
+        if !output.status.success() {
+            return Err(error(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+                output.status.code(),
+            )
+            .into());
+        }
+        Ok(output)
+    }
+
+    fn run_interactive(&self, description: &str, args: &[&str]) -> Result<(), GitError> {
+        let status = git_command()
+            .args(args)
+            .status()
+            .map_err(|e| spawn_error(description, args, e))?;
+        log_git_command(args, Some(status), "");
+        if !status.success() {
+            return Err(GitError {
+                description: description.to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                stderr: format!("git exited with {}", status),
+                not_found: false,
+                code: status.code(),
+            });
+        }
+        Ok(())
+    }
+}
 
 /// Run a git command and return the output
 ///
+/// Returns a `GitError` with `not_found` set if the git executable is not found.
+///
 /// # Arguments
 ///
+/// * `git` - The runner of the git command.
 /// * `description` - The description of the git command.
 /// * `args` - The arguments to pass to the git command.
-/// * `maybe_error` - Whether the git command might fail intentionally.
-/// * `verbose` - Whether to print the git command and its output.
+/// * `verbose` - The verbosity level: 1 prints the git command, 2 also its output, and 3 also its duration.
 ///
 /// # Returns
 ///
-/// * `std::process::Output` - The output of the git command.
+/// * `Ok(Output)` - The output of the git command if it succeeded.
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
 pub fn run_git_command(
+    git: &impl GitRunner,
     description: &str,
     args: &[&str],
-    maybe_error: bool,
-    verbose: bool,
-) -> Output {
-    if verbose {
+    verbose: u8,
+) -> Result<Output, GitError> {
+    if verbose >= 1 {
         println!("[git {}]", args.join(" ").yellow());
     }
-    let output = Command::new("git").args(args).output();
-    match output {
-        Ok(output) => {
-            if output.status.success() && !output.stdout.is_empty() && verbose {
-                println!("{}", String::from_utf8_lossy(&output.stdout));
-            }
-            if !output.status.success() && !maybe_error {
-                eprintln!("{}: Failed to {}.", "error".red().bold(), description);
-                eprintln!("Original error from git:");
-                eprintln!("\t{}", String::from_utf8_lossy(&output.stderr));
-                exit(1);
-            }
-            output
-        }
-        Err(e) => {
-            eprintln!("{}: Failed to {}.", "error".red().bold(), description);
-            eprintln!("{}", e);
-            exit(1);
-        }
+    let start = Instant::now();
+    let output = git.run(description, args);
+    if verbose >= 3 {
+        println!("[took {:.2?}]", start.elapsed());
+    }
+    let output = output?;
+    if !output.stdout.is_empty() && verbose >= 2 {
+        println!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    Ok(output)
+}
+
+/// Run a git command and stream its output to a writer as it is produced
+///
+/// Unlike `run_git_command`, the output is never held in memory as a whole, so this suits
+/// commands with large output that is not parsed, such as `git diff`. A closed writer (e.g. a pager
+/// that quit) stops git without an error.
+/// Returns a `GitError` with `not_found` set if the git executable is not found.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git command.
+/// * `description` - The description of the git command.
+/// * `args` - The arguments to pass to the git command.
+/// * `out` - The writer to copy the output of git to.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(())` - If the git command succeeded or the writer was closed.
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
+pub fn run_streaming_git_command(
+    git: &impl GitRunner,
+    description: &str,
+    args: &[&str],
+    out: &mut dyn Write,
+    verbose: u8,
+) -> Result<(), GitError> {
+    if verbose >= 1 {
+        println!("[git {}]", args.join(" ").yellow());
+    }
+    let start = Instant::now();
+    let result = git.run_streaming(description, args, out);
+    if verbose >= 3 {
+        println!("[took {:.2?}]", start.elapsed());
+    }
+    result
+}
+
+/// Run a git command like `run_git_command`, but kill it if it runs longer than the timeout
+///
+/// Meant for network-bound commands such as `git pull`, which can hang on an unreachable remote.
+/// Returns a `CrescaError::Git` with `not_found` set if the git executable is not found.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git command.
+/// * `description` - The description of the git command.
+/// * `args` - The arguments to pass to the git command.
+/// * `timeout` - How long to let git run.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(Output)` - The output of the git command if it succeeded in time.
+/// * `Err(CrescaError::GitTimeout)` - If git was killed after the timeout.
+/// * `Err(CrescaError::Git)` - If git could not be run or exited with a failure status.
+pub fn run_git_command_with_timeout(
+    git: &impl GitRunner,
+    description: &str,
+    args: &[&str],
+    timeout: Duration,
+    verbose: u8,
+) -> Result<Output, CrescaError> {
+    if verbose >= 1 {
+        println!("[git {}]", args.join(" ").yellow());
+    }
+    let start = Instant::now();
+    let output = git.run_with_timeout(description, args, timeout);
+    if verbose >= 3 {
+        println!("[took {:.2?}]", start.elapsed());
+    }
+    let output = output?;
+    if !output.stdout.is_empty() && verbose >= 2 {
+        println!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    Ok(output)
+}
+
+/// Run an interactive git command attached to the terminal
+///
+/// Unlike `run_git_command`, the output is not captured and the user's locale is kept.
+/// Returns a `GitError` with `not_found` set if the git executable is not found.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git command.
+/// * `description` - The description of the git command.
+/// * `args` - The arguments to pass to the git command.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(())` - If the git command succeeded.
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
+pub fn run_interactive_git_command(
+    git: &impl GitRunner,
+    description: &str,
+    args: &[&str],
+    verbose: u8,
+) -> Result<(), GitError> {
+    if verbose >= 1 {
+        println!("[git {}]", args.join(" ").yellow());
+    }
+    git.run_interactive(description, args)
+}
+
+/// Get the error of a git command whose executable could not be run, and log it
+///
+/// # Arguments
+///
+/// * `description` - The description of the git command.
+/// * `args` - The arguments passed to the git command.
+/// * `e` - The error of spawning the git executable.
+fn spawn_error(description: &str, args: &[&str], e: io::Error) -> GitError {
+    log_git_command(args, None, &e.to_string());
+    GitError {
+        description: description.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        stderr: e.to_string(),
+        not_found: e.kind() == ErrorKind::NotFound,
+        code: None,
+    }
+}
+
+/// Run a git command that modifies the repository, or only print it in dry-run mode
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `description` - The description of the git command.
+/// * `args` - The arguments to pass to the git command.
+/// * `dry_run` - Whether to print the git command instead of running it.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(Output)` - The output of the git command, or an empty successful output in dry-run mode.
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
+pub fn run_mutating_git_command(
+    git: &impl GitRunner,
+    description: &str,
+    args: &[&str],
+    dry_run: bool,
+    verbose: u8,
+) -> Result<Output, GitError> {
+    if dry_run {
+        println!("[would run: git {}]", args.join(" ").yellow());
+        return Ok(Output {
+            status: ExitStatus::default(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    }
+    run_git_command(git, description, args, verbose)
+}
+
+/// Check if the current directory is inside a git work tree
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether the current directory is inside a git work tree
+/// * `Err(GitError)` - If the git executable was not found
+pub fn is_git_repo(git: &impl GitRunner, verbose: u8) -> Result<bool, GitError> {
+    match run_git_command(
+        git,
+        "check whether inside a git repository",
+        &["rev-parse", "--is-inside-work-tree"],
+        verbose,
+    ) {
+        Ok(output) => Ok(String::from_utf8_lossy(&output.stdout).trim() == "true"),
+        Err(e) if e.not_found => Err(e),
+        Err(_) => Ok(false),
     }
 }
 
@@ -48,62 +671,843 @@ pub fn run_git_command(
 ///
 /// # Arguments
 ///
-/// * `verbose` - Whether to print the git command and its output.
-pub fn is_clean(verbose: bool) -> bool {
-    run_git_command(
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn is_clean(git: &impl GitRunner, verbose: u8) -> Result<bool, GitError> {
+    Ok(run_git_command(
+        git,
         "check working directory status",
         &["status", "--porcelain"],
-        false,
         verbose,
-    )
+    )?
     .stdout
-    .is_empty()
+    .is_empty())
 }
 
-/// Check if the current branch is a review branch
+/// Get the name of the current branch
 ///
 /// # Arguments
 ///
-/// * `verbose` - Whether to print the git command and its output.
-pub fn is_review_branch(verbose: bool) -> bool {
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+#[cfg(not(feature = "libgit2"))]
+pub fn get_current_branch(git: &impl GitRunner, verbose: u8) -> Result<String, GitError> {
     let output = run_git_command(
+        git,
         "get current branch",
         &["rev-parse", "--abbrev-ref", "HEAD"],
-        false,
         verbose,
-    );
-    let branch_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    branch_name.starts_with("review")
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-/// Get review branch info (to_branch, from_branch) from current branch name
+/// Check if HEAD is detached
 ///
 /// # Arguments
 ///
-/// * `verbose` - Whether to print the git command and its output.
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
 ///
 /// # Returns
 ///
-/// * `Option<(String, String)>` - (to_branch, from_branch) if on a review branch, None otherwise
-pub fn get_review_branch_info(verbose: bool) -> Option<(String, String)> {
+/// * `Ok(bool)` - Whether HEAD is detached.
+/// * `Err(GitError)` - If git could not be run or failed for another reason, e.g. outside of a repository.
+pub fn is_detached_head(git: &impl GitRunner, verbose: u8) -> Result<bool, GitError> {
+    match run_git_command(
+        git,
+        "check whether HEAD is detached",
+        &["symbolic-ref", "--quiet", "HEAD"],
+        verbose,
+    ) {
+        Ok(_) => Ok(false),
+        // `git symbolic-ref --quiet` exits with 1 without a message only if HEAD is not a symbolic ref
+        Err(e) if e.code == Some(1) && e.stderr.trim().is_empty() => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check if a local branch exists
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `branch` - The name of the local branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn branch_exists(git: &impl GitRunner, branch: &str, verbose: u8) -> bool {
+    run_git_command(
+        git,
+        &format!("check existence of {} branch", branch),
+        &["show-ref", "--verify", &format!("refs/heads/{}", branch)],
+        verbose,
+    )
+    .is_ok()
+}
+
+/// List the names of the local branches
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn list_branches(git: &impl GitRunner, verbose: u8) -> Result<Vec<String>, GitError> {
     let output = run_git_command(
-        "get current branch",
-        &["rev-parse", "--abbrev-ref", "HEAD"],
-        false,
+        git,
+        "list local branches",
+        &["branch", "--format=%(refname:short)"],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Check if a name refers to a tag or a commit rather than a branch
+///
+/// Names that only exist as a remote-tracking branch of `remote` are treated as branches,
+/// because `git switch` creates the local branch for them.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `name` - The tag, commit or branch name.
+/// * `remote` - The remote to look for remote-tracking branches in.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn is_fixed_revision(git: &impl GitRunner, name: &str, remote: &str, verbose: u8) -> bool {
+    let ref_exists = |reference: String| {
+        run_git_command(
+            git,
+            &format!("check existence of {}", reference),
+            &["show-ref", "--verify", "--quiet", &reference],
+            verbose,
+        )
+        .is_ok()
+    };
+    if branch_exists(git, name, verbose) {
+        return false;
+    }
+    if ref_exists(format!("refs/tags/{}", name)) {
+        return true;
+    }
+    !ref_exists(format!("refs/remotes/{}/{}", remote, name))
+        && rev_parse(git, name, verbose).is_ok()
+}
+
+/// Get the merge base of two branches
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+#[cfg(not(feature = "libgit2"))]
+pub fn get_merge_base(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<String, GitError> {
+    let output = run_git_command(
+        git,
+        "get merge base",
+        &["merge-base", to_branch, from_branch],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Check if two revisions have no common ancestor
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `bool` - True if both revisions exist and have no merge base, false otherwise
+pub fn have_unrelated_histories(
+    git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> bool {
+    rev_parse(git, to_branch, verbose).is_ok()
+        && rev_parse(git, from_branch, verbose).is_ok()
+        && get_merge_base(git, to_branch, from_branch, verbose).is_err()
+}
+
+/// Write the empty tree to the object database
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(String)` - The hash of the empty tree
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
+pub fn empty_tree(git: &impl GitRunner, verbose: u8) -> Result<String, GitError> {
+    // `mktree` reads the entries from stdin, which is empty
+    let output = run_git_command(git, "write empty tree", &["mktree"], verbose)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Create a root commit with the empty tree, without updating any branch
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `message` - The commit message.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(String)` - The hash of the commit
+/// * `Err(GitError)` - If git could not be run or exited with a failure status.
+pub fn create_empty_root_commit(
+    git: &impl GitRunner,
+    message: &str,
+    verbose: u8,
+) -> Result<String, GitError> {
+    let tree = empty_tree(git, verbose)?;
+    let output = run_git_command(
+        git,
+        "create empty root commit",
+        &["commit-tree", &tree, "-m", message],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Diff stats between two revisions
+#[derive(Default)]
+pub struct DiffStats {
+    /// Number of changed files.
+    pub file_count: usize,
+    /// Number of inserted lines in text files.
+    pub insertions: usize,
+    /// Number of deleted lines in text files.
+    pub deletions: usize,
+    /// Number of changed binary files, whose lines are not counted.
+    pub binary_files: usize,
+}
+
+/// Turn paths relative to the repository root into pathspecs that do not depend on the working directory
+///
+/// # Arguments
+///
+/// * `paths` - The paths relative to the repository root.
+pub fn top_pathspecs(paths: &[String]) -> Vec<String> {
+    paths.iter().map(|path| format!(":(top){}", path)).collect()
+}
+
+/// Diff stats of a single file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStat {
+    /// The path of the file, or `old -> new` for renamed files.
+    pub path: String,
+    /// Number of inserted lines, or 0 for binary files.
+    pub insertions: usize,
+    /// Number of deleted lines, or 0 for binary files.
+    pub deletions: usize,
+    /// Whether the file is binary.
+    pub binary: bool,
+}
+
+impl From<&[FileStat]> for DiffStats {
+    /// Sum up the diff stats of each file
+    fn from(file_stats: &[FileStat]) -> Self {
+        let mut stats = DiffStats {
+            file_count: file_stats.len(),
+            ..DiffStats::default()
+        };
+        for file in file_stats {
+            if file.binary {
+                stats.binary_files += 1;
+            } else {
+                stats.insertions += file.insertions;
+                stats.deletions += file.deletions;
+            }
+        }
+        stats
+    }
+}
+
+/// Get the diff stats of each file changed between two revisions
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from` - The revision to compare from.
+/// * `to` - The revision to compare to.
+/// * `paths` - Paths relative to the repository root to limit the diff to. All files are included if empty.
+/// * `ignore_eol` - Whether to ignore carriage returns at the end of lines, so files differing only
+///   in line endings are not listed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+#[cfg(not(feature = "libgit2"))]
+pub fn get_file_stats(
+    git: &impl GitRunner,
+    from: &str,
+    to: &str,
+    paths: &[String],
+    ignore_eol: bool,
+    verbose: u8,
+) -> Result<Vec<FileStat>, GitError> {
+    diff_file_stats(git, from, to, paths, ignore_eol, verbose)
+}
+
+/// Get the diff stats of each file changed between two revisions by running `git diff`
+///
+/// Used by `get_file_stats`, and by its libgit2 counterpart for `--ignore-cr-at-eol`, which
+/// libgit2 does not support.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from` - The revision to compare from.
+/// * `to` - The revision to compare to.
+/// * `paths` - Paths relative to the repository root to limit the diff to. All files are included if empty.
+/// * `ignore_eol` - Whether to ignore carriage returns at the end of lines.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub(crate) fn diff_file_stats(
+    git: &impl GitRunner,
+    from: &str,
+    to: &str,
+    paths: &[String],
+    ignore_eol: bool,
+    verbose: u8,
+) -> Result<Vec<FileStat>, GitError> {
+    let pathspecs = top_pathspecs(paths);
+    let mut args = vec!["diff", "--numstat", "-z", "-M"];
+    if ignore_eol {
+        args.push("--ignore-cr-at-eol");
+    }
+    args.extend([from, to, "--"]);
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = run_git_command(git, "get file stats", &args, verbose)?;
+
+    // Each record is `<insertions>\t<deletions>\t<path>\0`, or `<insertions>\t<deletions>\t\0<old>\0<new>\0`
+    // for renames. Binary files have `-` instead of the line counts.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut records = stdout.split('\0');
+    let mut stats = Vec::new();
+    while let Some(record) = records.next() {
+        let mut fields = record.splitn(3, '\t');
+        let (Some(insertions), Some(deletions), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let path = if path.is_empty() {
+            let (Some(old_path), Some(new_path)) = (records.next(), records.next()) else {
+                break;
+            };
+            format!("{} -> {}", old_path, new_path)
+        } else {
+            path.to_string()
+        };
+        let (insertions, deletions) = (insertions.parse().ok(), deletions.parse().ok());
+        stats.push(FileStat {
+            path,
+            insertions: insertions.unwrap_or(0),
+            deletions: deletions.unwrap_or(0),
+            binary: insertions.is_none(),
+        });
+    }
+
+    Ok(stats)
+}
+
+/// List the commits in a range, newest first
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `range` - The commit range (e.g. `main..develop`).
+/// * `verbose` - The verbosity level (see `run_git_command`).
+#[cfg(not(feature = "libgit2"))]
+pub fn rev_list(git: &impl GitRunner, range: &str, verbose: u8) -> Result<Vec<String>, GitError> {
+    let output = run_git_command(git, "list commits", &["rev-list", range], verbose)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Resolve a revision to a commit hash
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `revision` - The revision to resolve (e.g. a branch name or a commit hash).
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn rev_parse(git: &impl GitRunner, revision: &str, verbose: u8) -> Result<String, GitError> {
+    let output = run_git_command(
+        git,
+        &format!("resolve {}", revision),
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", revision),
+        ],
+        verbose,
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Check if a commit is a merge commit, i.e. has more than one parent
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `commit` - The commit to check.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn is_merge_commit(git: &impl GitRunner, commit: &str, verbose: u8) -> bool {
+    run_git_command(
+        git,
+        &format!("check whether {} is a merge commit", commit),
+        &["rev-parse", "--verify", "--quiet", &format!("{}^2", commit)],
+        verbose,
+    )
+    .is_ok()
+}
+
+/// Check if a remote exists
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `remote` - The name of the remote.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn remote_exists(git: &impl GitRunner, remote: &str, verbose: u8) -> Result<bool, GitError> {
+    let output = run_git_command(git, "list remotes", &["remote"], verbose)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line.trim() == remote))
+}
+
+/// Get the default branch of the repository
+///
+/// Uses `<remote>/HEAD` when it is known, otherwise falls back to a local `main` or `master` branch.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `remote` - The remote whose HEAD names the default branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<String>` - The default branch name if it could be determined, None otherwise
+pub fn get_default_branch(git: &impl GitRunner, remote: &str, verbose: u8) -> Option<String> {
+    if let Ok(output) = run_git_command(
+        git,
+        "get default branch",
+        &[
+            "symbolic-ref",
+            "--short",
+            &format!("refs/remotes/{}/HEAD", remote),
+        ],
+        verbose,
+    ) {
+        let remote_head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if let Some(branch) = remote_head.strip_prefix(&format!("{}/", remote)) {
+            if branch_exists(git, branch, verbose) {
+                return Some(branch.to_string());
+            }
+        }
+    }
+
+    ["main", "master"]
+        .iter()
+        .find(|branch| branch_exists(git, branch, verbose))
+        .map(|branch| branch.to_string())
+}
+
+/// Default prefix of review branch names
+pub const DEFAULT_PREFIX: &str = "review";
+
+/// Check if the current branch is a review branch
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `prefix` - The prefix of review branch names.
+/// * `current_branch` - The name of the current branch if it is already known, so it is not resolved again.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn is_review_branch(
+    git: &impl GitRunner,
+    prefix: &str,
+    current_branch: Option<&str>,
+    verbose: u8,
+) -> Result<bool, GitError> {
+    Ok(match current_branch {
+        Some(branch) => branch.starts_with(prefix),
+        None => get_current_branch(git, verbose)?.starts_with(prefix),
+    })
+}
+
+/// Build the review branch name for the given branches
+///
+/// Hyphens and slashes in the branch names are percent-encoded so that the `{prefix}-{to}-{from}`
+/// format can be split unambiguously and the review branch is a single flat ref.
+///
+/// # Arguments
+///
+/// * `prefix` - The prefix of review branch names.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+pub fn review_branch_name(prefix: &str, to_branch: &str, from_branch: &str) -> String {
+    format!(
+        "{}-{}-{}",
+        prefix,
+        encode_branch_component(to_branch),
+        encode_branch_component(from_branch)
+    )
+}
+
+/// Parse a review branch name built by `review_branch_name`
+///
+/// # Arguments
+///
+/// * `prefix` - The prefix of review branch names.
+/// * `branch_name` - The name of the review branch.
+///
+/// # Returns
+///
+/// * `Option<(String, String)>` - (to_branch, from_branch) if the name is a review branch name, None otherwise
+pub fn parse_review_branch_name(prefix: &str, branch_name: &str) -> Option<(String, String)> {
+    let rest = branch_name.strip_prefix(prefix)?.strip_prefix('-')?;
+    let (to_branch, from_branch) = rest.split_once('-')?;
+    Some((
+        decode_branch_component(to_branch),
+        decode_branch_component(from_branch),
+    ))
+}
+
+/// Percent-encode `%`, `-` and `/` in a branch name
+fn encode_branch_component(name: &str) -> String {
+    name.replace('%', "%25")
+        .replace('-', "%2D")
+        .replace('/', "%2F")
+}
+
+/// Decode a branch name encoded by `encode_branch_component`
+fn decode_branch_component(name: &str) -> String {
+    let mut decoded = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(pos) = rest.find('%') {
+        decoded.push_str(&rest[..pos]);
+        let escape = &rest[pos..];
+        if let Some(after) = escape.strip_prefix("%2D") {
+            decoded.push('-');
+            rest = after;
+        } else if let Some(after) = escape.strip_prefix("%2F") {
+            decoded.push('/');
+            rest = after;
+        } else if let Some(after) = escape.strip_prefix("%25") {
+            decoded.push('%');
+            rest = after;
+        } else {
+            decoded.push('%');
+            rest = &escape[1..];
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Get a value from the repository's git config
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to read.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<String>` - The config value if the key is set, None otherwise
+pub fn get_config(git: &impl GitRunner, key: &str, verbose: u8) -> Option<String> {
+    let output = run_git_command(
+        git,
+        &format!("read {} config", key),
+        &["config", "--get", key],
+        verbose,
+    )
+    .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Set a value in the repository's git config
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to write.
+/// * `value` - The value to write.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn set_config(
+    git: &impl GitRunner,
+    key: &str,
+    value: &str,
+    verbose: u8,
+) -> Result<(), GitError> {
+    run_git_command(
+        git,
+        &format!("write {} config", key),
+        &["config", key, value],
+        verbose,
+    )?;
+    Ok(())
+}
+
+/// Get all values of a multi-valued key from git config
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to read.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Vec<String>` - The values, or an empty list if the key is not set or git config cannot be read
+pub fn get_config_all(git: &impl GitRunner, key: &str, verbose: u8) -> Vec<String> {
+    try_get_config_all(git, key, verbose).unwrap_or_default()
+}
+
+/// Get all values of a multi-valued key from git config, failing if git config cannot be read
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to read.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - The values, or an empty list if the key is not set.
+/// * `Err(GitError)` - If git config could not be read.
+pub fn try_get_config_all(
+    git: &impl GitRunner,
+    key: &str,
+    verbose: u8,
+) -> Result<Vec<String>, GitError> {
+    match run_git_command(
+        git,
+        &format!("read all {} config", key),
+        &["config", "--get-all", key],
+        verbose,
+    ) {
+        Ok(output) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect()),
+        // git config fails silently for an unset key, and reports every other failure on stderr
+        Err(e) if e.stderr.trim().is_empty() && !e.not_found => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace all values of a multi-valued key in the repository's git config
+///
+/// The key is removed if `values` is empty.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to write.
+/// * `values` - The values to write.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn set_config_all(
+    git: &impl GitRunner,
+    key: &str,
+    values: &[String],
+    verbose: u8,
+) -> Result<(), GitError> {
+    // The key may not be set
+    let _ = run_git_command(
+        git,
+        &format!("remove {} config", key),
+        &["config", "--unset-all", key],
+        verbose,
+    );
+    for value in values {
+        run_git_command(
+            git,
+            &format!("write {} config", key),
+            &["config", "--add", key, value],
+            verbose,
+        )?;
+    }
+    Ok(())
+}
+
+/// Remove a value from the repository's git config if it is set
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `key` - The config key to remove.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn unset_config(git: &impl GitRunner, key: &str, verbose: u8) {
+    // The key may not be set
+    let _ = run_git_command(
+        git,
+        &format!("remove {} config", key),
+        &["config", "--unset", key],
         verbose,
     );
-    let branch_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+}
 
-    if !branch_name.starts_with("review-") {
+/// Check whether the commits created by cresca should be signed
+///
+/// Reads the `cresca.sign` git config, falling back to `commit.gpgsign`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `bool` - True if signing is enabled, false otherwise
+pub fn signing_enabled(git: &impl GitRunner, verbose: u8) -> bool {
+    let get_bool = |key: &str| {
+        let output = run_git_command(
+            git,
+            &format!("read {} config", key),
+            &["config", "--type=bool", "--get", key],
+            verbose,
+        )
+        .ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).trim() == "true")
+    };
+    get_bool("cresca.sign")
+        .or_else(|| get_bool("commit.gpgsign"))
+        .unwrap_or(false)
+}
+
+/// Store review branch info in the git config under the `cresca.<review_branch>` section
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `skip_to` - Optional commit hash the review was skipped to.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn set_review_branch_info(
+    git: &impl GitRunner,
+    review_branch: &str,
+    to_branch: &str,
+    from_branch: &str,
+    skip_to: Option<&str>,
+    verbose: u8,
+) -> Result<(), GitError> {
+    set_config(
+        git,
+        &format!("cresca.{}.to", review_branch),
+        to_branch,
+        verbose,
+    )?;
+    set_config(
+        git,
+        &format!("cresca.{}.from", review_branch),
+        from_branch,
+        verbose,
+    )?;
+    let skip_to_key = format!("cresca.{}.skipTo", review_branch);
+    match skip_to {
+        Some(hash) => set_config(git, &skip_to_key, hash, verbose)?,
+        None => unset_config(git, &skip_to_key, verbose),
+    }
+    Ok(())
+}
+
+/// Remove review branch info from the git config
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn remove_review_branch_info(git: &impl GitRunner, review_branch: &str, verbose: u8) {
+    // Review branches created by older versions have no stored info
+    let _ = run_git_command(
+        git,
+        "remove review branch config",
+        &[
+            "config",
+            "--remove-section",
+            &format!("cresca.{}", review_branch),
+        ],
+        verbose,
+    );
+}
+
+/// Get review branch info (to_branch, from_branch) of the current branch
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `prefix` - The prefix of review branch names.
+/// * `current_branch` - The name of the current branch if it is already known, so it is not resolved again.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<(String, String)>` - (to_branch, from_branch) if on a review branch, None otherwise
+pub fn get_review_branch_info(
+    git: &impl GitRunner,
+    prefix: &str,
+    current_branch: Option<&str>,
+    verbose: u8,
+) -> Result<Option<(String, String)>, GitError> {
+    Ok(match current_branch {
+        Some(branch) => get_branch_review_info(git, prefix, branch, verbose),
+        None => get_branch_review_info(git, prefix, &get_current_branch(git, verbose)?, verbose),
+    })
+}
+
+/// Get review branch info (to_branch, from_branch) of the given branch
+///
+/// Reads the info stored in the git config by `cresca review`, and falls back to parsing the
+/// branch name for review branches created by older versions.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `prefix` - The prefix of review branch names.
+/// * `branch_name` - The name of the branch.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// * `Option<(String, String)>` - (to_branch, from_branch) if the branch is a review branch, None otherwise
+pub fn get_branch_review_info(
+    git: &impl GitRunner,
+    prefix: &str,
+    branch_name: &str,
+    verbose: u8,
+) -> Option<(String, String)> {
+    if !branch_name.starts_with(&format!("{}-", prefix)) {
         return None;
     }
 
-    // Parse "review-{to}-{from}" format
-    let rest = branch_name.strip_prefix("review-")?;
-    let parts: Vec<&str> = rest.splitn(2, '-').collect();
-    if parts.len() == 2 {
-        Some((parts[0].to_string(), parts[1].to_string()))
-    } else {
-        None
+    let to_branch = get_config(git, &format!("cresca.{}.to", branch_name), verbose);
+    let from_branch = get_config(git, &format!("cresca.{}.from", branch_name), verbose);
+    if let (Some(to_branch), Some(from_branch)) = (to_branch, from_branch) {
+        return Some((to_branch, from_branch));
     }
+
+    parse_review_branch_name(prefix, branch_name)
 }
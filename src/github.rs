@@ -0,0 +1,61 @@
+use crate::error::CrescaError;
+use colored::Colorize;
+use serde::Deserialize;
+use std::io::ErrorKind;
+use std::process::Command;
+
+/// Branches of a GitHub pull request
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    /// The branch where the PR is planned to be merged into.
+    pub base_ref_name: String,
+    /// The development branch of the PR.
+    pub head_ref_name: String,
+}
+
+/// Get the branches of a pull request using the GitHub CLI (`gh`)
+///
+/// `gh` reads the token from `GH_TOKEN` if it is set.
+///
+/// # Arguments
+///
+/// * `number` - The pull request number.
+/// * `verbose` - The verbosity level: 1 prints the gh command and 2 also its output.
+///
+/// # Returns
+///
+/// * `Ok(PullRequest)` - The base and head branches of the pull request
+/// * `Err(CrescaError)` - If gh is not installed, failed, or printed an unexpected output
+pub fn get_pull_request(number: u64, verbose: u8) -> Result<PullRequest, CrescaError> {
+    let number = number.to_string();
+    let args = ["pr", "view", &number, "--json", "baseRefName,headRefName"];
+    if verbose >= 1 {
+        println!("[gh {}]", args.join(" ").yellow());
+    }
+
+    let output = Command::new("gh").args(args).output().map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            CrescaError::GitHubCliNotFound
+        } else {
+            CrescaError::GitHub {
+                description: "run gh".to_string(),
+                detail: e.to_string(),
+            }
+        }
+    })?;
+    if !output.status.success() {
+        return Err(CrescaError::GitHub {
+            description: format!("get pull request #{}", number),
+            detail: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+    if verbose >= 2 {
+        println!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| CrescaError::GitHub {
+        description: format!("parse pull request #{}", number),
+        detail: e.to_string(),
+    })
+}
@@ -0,0 +1,33 @@
+//! A tool to partially review the pull requests.
+//!
+//! The review is done on a review branch prepared from the merge base of the two branches.
+//! Reviewed changes are committed to the review branch, so only the unreviewed changes remain
+//! in the diff to the development branch.
+//!
+//! All functions run git in the current working directory, or in the directory set by
+//! `git::set_git_work_dir`. Preparing a review branch in a worktree sets it to the worktree.
+
+pub mod commands;
+pub mod config;
+pub mod error;
+pub mod git;
+#[cfg(feature = "github")]
+pub mod github;
+#[cfg(feature = "libgit2")]
+mod libgit2;
+pub mod messages;
+mod progress;
+
+pub use commands::{
+    abort_review, approve_changes, clean_review_branches, confirm_discarding_changes,
+    confirm_recreating_review_branch, continue_review, current_review_commit, finalize_review,
+    get_review_log, get_review_patch, get_review_status, interrupt_requested, list_review_branches,
+    prepare_review_branch, preview_review, request_interrupt, review_next_commit,
+    review_worktree_path, stage_all_changes, stage_files_interactively, stage_hunks_interactively,
+    stage_listed_files, stage_paths, stash_changes, switch_review_branch, unapprove_paths,
+    undo_approval, unstage_paths, validate_author, write_review_diff, CleanResult, CommitAction,
+    ReviewBranch, ReviewLog, ReviewOptions, ReviewStatus,
+};
+pub use config::{load_config, Config};
+pub use error::CrescaError;
+pub use git::GitError;
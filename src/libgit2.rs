@@ -0,0 +1,187 @@
+//! Read-only git operations implemented with libgit2, enabled by the `libgit2` feature.
+//!
+//! The functions have the same signatures as their process-based counterparts in `git`.
+
+use crate::git::{diff_file_stats, git_work_dir, FileStat, GitError, GitRunner};
+use colored::Colorize;
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Oid, Patch, Repository};
+
+/// Convert a libgit2 error into a `GitError`
+fn to_git_error(description: &str, args: &[&str], e: git2::Error) -> GitError {
+    GitError {
+        description: description.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        stderr: e.message().to_string(),
+        not_found: false,
+        code: None,
+    }
+}
+
+/// Open the repository of the current working directory, or of the directory set by
+/// `set_git_work_dir`, and run an operation on it
+///
+/// # Arguments
+///
+/// * `description` - The description of the operation.
+/// * `args` - The equivalent git command arguments, printed in verbose mode and used in errors.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+/// * `operation` - The operation to run.
+fn with_repo<T>(
+    description: &str,
+    args: &[&str],
+    verbose: u8,
+    operation: impl FnOnce(&Repository) -> Result<T, git2::Error>,
+) -> Result<T, GitError> {
+    if verbose >= 1 {
+        println!("[libgit2: git {}]", args.join(" ").yellow());
+    }
+    let repo = match git_work_dir() {
+        Some(dir) => Repository::discover(dir),
+        None => Repository::open_from_env(),
+    };
+    repo.and_then(|repo| operation(&repo))
+        .map_err(|e| to_git_error(description, args, e))
+}
+
+/// Resolve a revision to a commit id
+fn resolve_commit(repo: &Repository, revision: &str) -> Result<Oid, git2::Error> {
+    Ok(repo.revparse_single(revision)?.peel_to_commit()?.id())
+}
+
+/// Get the diff between the trees of two revisions, with renames detected like `git diff`
+fn diff_revisions<'a>(
+    repo: &'a Repository,
+    from: &str,
+    to: &str,
+    paths: &[String],
+) -> Result<Diff<'a>, git2::Error> {
+    let from_tree = repo.revparse_single(from)?.peel_to_tree()?;
+    let to_tree = repo.revparse_single(to)?.peel_to_tree()?;
+    let mut options = DiffOptions::new();
+    for path in paths {
+        options.pathspec(path);
+    }
+    let mut diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut options))?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+    Ok(diff)
+}
+
+/// Get the name of the current branch
+///
+/// # Arguments
+///
+/// * `_git` - The runner of the git commands, unused as libgit2 reads the repository itself.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn get_current_branch(_git: &impl GitRunner, verbose: u8) -> Result<String, GitError> {
+    let args = ["rev-parse", "--abbrev-ref", "HEAD"];
+    with_repo("get current branch", &args, verbose, |repo| {
+        // Match `git rev-parse --abbrev-ref HEAD`, which prints `HEAD` when detached
+        if repo.head_detached()? {
+            return Ok("HEAD".to_string());
+        }
+        let head = repo.find_reference("HEAD")?;
+        let target = head.symbolic_target().unwrap_or("HEAD");
+        Ok(target
+            .strip_prefix("refs/heads/")
+            .unwrap_or(target)
+            .to_string())
+    })
+}
+
+/// Get the merge base of two branches
+///
+/// # Arguments
+///
+/// * `_git` - The runner of the git commands, unused as libgit2 reads the repository itself.
+/// * `to_branch` - The branch where the PR is planned to be merged into.
+/// * `from_branch` - The development branch to be reviewed.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn get_merge_base(
+    _git: &impl GitRunner,
+    to_branch: &str,
+    from_branch: &str,
+    verbose: u8,
+) -> Result<String, GitError> {
+    let args = ["merge-base", to_branch, from_branch];
+    with_repo("get merge base", &args, verbose, |repo| {
+        let to = resolve_commit(repo, to_branch)?;
+        let from = resolve_commit(repo, from_branch)?;
+        Ok(repo.merge_base(to, from)?.to_string())
+    })
+}
+
+/// List the commits in a range, newest first
+///
+/// # Arguments
+///
+/// * `_git` - The runner of the git commands, unused as libgit2 reads the repository itself.
+/// * `range` - The commit range (e.g. `main..develop`).
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn rev_list(_git: &impl GitRunner, range: &str, verbose: u8) -> Result<Vec<String>, GitError> {
+    let args = ["rev-list", range];
+    with_repo("list commits", &args, verbose, |repo| {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_range(range)?;
+        revwalk.map(|oid| oid.map(|oid| oid.to_string())).collect()
+    })
+}
+
+/// Get the diff stats of each file changed between two revisions
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `from` - The revision to compare from.
+/// * `to` - The revision to compare to.
+/// * `paths` - Paths relative to the repository root to limit the diff to. All files are included if empty.
+/// * `ignore_eol` - Whether to ignore carriage returns at the end of lines, so files differing only
+///   in line endings are not listed. libgit2 can only ignore all whitespace at the end of lines, so
+///   git is run instead.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+pub fn get_file_stats(
+    git: &impl GitRunner,
+    from: &str,
+    to: &str,
+    paths: &[String],
+    ignore_eol: bool,
+    verbose: u8,
+) -> Result<Vec<FileStat>, GitError> {
+    if ignore_eol {
+        return diff_file_stats(git, from, to, paths, ignore_eol, verbose);
+    }
+    let args = ["diff", "--numstat", "-M", from, to];
+    with_repo("get file stats", &args, verbose, |repo| {
+        let diff = diff_revisions(repo, from, to, paths)?;
+        let mut stats = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            let binary = delta.flags().is_binary();
+            let (insertions, deletions) = if binary {
+                (0, 0)
+            } else {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            };
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            let path = path.to_string_lossy().to_string();
+            let path = match (delta.status(), delta.old_file().path()) {
+                (Delta::Renamed, Some(old_path)) => {
+                    format!("{} -> {}", old_path.to_string_lossy(), path)
+                }
+                _ => path,
+            };
+            stats.push(FileStat {
+                path,
+                insertions,
+                deletions,
+                binary,
+            });
+        }
+        Ok(stats)
+    })
+}
@@ -1,12 +1,36 @@
-mod commands;
-mod git;
-
 use clap::builder::styling::{AnsiColor, Effects};
-use clap::{builder::Styles, ArgAction, Args, Parser, Subcommand};
+use clap::{builder::Styles, ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use colored::Colorize;
-use commands::{approve_changes, get_review_status, prepare_review_branch};
-use git::{get_review_branch_info, is_clean, is_review_branch};
+use cresca::commands::{
+    abort_review, approve_changes, clean_review_branches, confirm_discarding_changes,
+    confirm_recreating_review_branch, continue_review, current_review_commit, finalize_review,
+    get_review_log, get_review_patch, get_review_status, interrupt_requested, list_review_branches,
+    prepare_review_branch, preview_review, request_interrupt, review_next_commit,
+    review_worktree_path, stage_all_changes, stage_files_interactively, stage_hunks_interactively,
+    stage_listed_files, stage_paths, stash_changes, switch_review_branch, unapprove_paths,
+    undo_approval, unstage_paths, validate_author, write_review_diff, CommitAction, ReviewOptions,
+    ReviewStatus,
+};
+use cresca::config::{load_config, resolve_git_config_key, Config, GIT_CONFIG_KEYS};
+use cresca::error::CrescaError;
+use cresca::git::{
+    get_branch_review_info, get_config, get_config_all, get_current_branch, get_review_branch_info,
+    is_clean, is_detached_head, is_git_repo, is_review_branch, list_branches, remote_exists,
+    rev_list, rev_parse, review_branch_name, run_git_command, set_config, set_git_binary,
+    set_log_file, signing_enabled, top_pathspecs, try_get_config_all, GitRunner, ProcessGitRunner,
+    DEFAULT_PREFIX,
+};
+#[cfg(feature = "github")]
+use cresca::github::get_pull_request;
+use cresca::messages::{fill, fill_plural, msg, Id};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
 use std::process::exit;
+use std::thread;
+use std::time::Duration;
+use terminal_size::{terminal_size_of, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 const STYLES: Styles = Styles::styled()
     .header(AnsiColor::Green.on_default().effects(Effects::BOLD))
@@ -31,110 +55,1862 @@ With this tool you can identify which changes are already reviewed and which are
 struct Cli {
     #[command(subcommand)]
     command: Commands,
-    /// Print executed git commands and their output.
+    /// Print executed git commands. Repeat to also print their output (`-vv`) and duration (`-vvv`).
+    #[arg(short = 'v', global = true, action = ArgAction::Count)]
+    verbosity: u8,
+    /// Print executed git commands and their output. Same as `-vv`.
     #[arg(long, global = true, action = ArgAction::SetTrue)]
     verbose: bool,
+    /// Suppress informational output. Errors are still printed to stderr.
+    #[arg(short, long, global = true, action = ArgAction::SetTrue)]
+    quiet: bool,
+    /// Disable colored output. Also disabled by the `NO_COLOR` environment variable or when stdout is not a terminal.
+    #[arg(long = "no-color", global = true, action = ArgAction::SetTrue)]
+    no_color: bool,
+    /// Use plain ASCII labels instead of emoji. Also enabled by the `CRESCA_NO_EMOJI` environment variable or when stdout is not a terminal.
+    #[arg(long = "no-emoji", global = true, action = ArgAction::SetTrue)]
+    no_emoji: bool,
+    /// The git executable to use [default: git on PATH].
+    #[arg(
+        long = "git-binary",
+        global = true,
+        env = "CRESCA_GIT",
+        value_name = "PATH"
+    )]
+    git_binary: Option<String>,
+    /// Append every git command run, with its time, exit status and the stderr of failures, to this file.
+    #[arg(
+        long = "log-file",
+        global = true,
+        env = "CRESCA_LOG_FILE",
+        value_name = "PATH"
+    )]
+    log_file: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Get the verbosity level, where `--verbose` counts as `-vv`
+    fn verbose_level(&self) -> u8 {
+        if self.verbose {
+            self.verbosity.max(2)
+        } else {
+            self.verbosity
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Abort the review by discarding all changes and deleting the review branch.
+    Abort,
+    /// Mark the changes of the given paths as reviewed by staging them for approval.
+    Add(PathsArgs),
     /// Partially approve the reviewed changes by committing and discard unreviewed changes.
-    Approve,
+    Approve(ApproveArgs),
+    /// Delete review branches whose development branch has been merged or no longer exists.
+    Clean(CleanArgs),
+    /// Print the completion candidates for a partial command line. Used by the completion scripts.
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
+    /// Generate a shell completion script.
+    #[command(hide = true)]
+    Completions(CompletionsArgs),
+    /// Get, set or list the cresca settings in the git config.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Continue preparing the review branch after resolving merge conflicts.
+    Continue,
+    /// Show the remaining unreviewed diff.
+    Diff(DiffArgs),
+    /// Check the environment and the repository state, e.g. for bug reports.
+    Doctor,
+    /// Export the approved changes as a patch series that can be applied with `git am`.
+    Export(ExportArgs),
+    /// Merge the approved changes into the target branch and switch to it.
+    Finalize(FinalizeArgs),
+    /// List review branches.
+    List,
+    /// Show approved commits on the review branch and commits remaining on the development branch.
+    Log,
+    /// Unmark the changes of the given paths as reviewed by unstaging them.
+    Reset(PathsArgs),
     /// Prepare a review branch.
-    Review(ReviewArgs),
+    Review(Box<ReviewArgs>),
+    /// Show the remaining diff and the progress of every review branch.
+    Stats,
     /// Show remaining diff statistics.
-    Status,
+    Status(StatusArgs),
+    /// Switch to the review branch of the given branches.
+    Switch(SwitchArgs),
+    /// Move files approved in the most recent approval back into review as unstaged changes.
+    Unapprove(UnapproveArgs),
+    /// Undo the most recent approval so its changes become unreviewed again.
+    Undo,
+}
+
+#[derive(Args)]
+struct ApproveArgs {
+    /// The commit message for the approved changes [default: `approve_message` in the config file, or "Approve reviewed changes"].
+    #[arg(short, long)]
+    message: Option<String>,
+    /// Choose the files to approve interactively. Ignored if stdin is not a terminal.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    interactive: bool,
+    /// Approve all remaining changes, including untracked files.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "interactive")]
+    all: bool,
+    /// Choose the hunks to approve interactively with `git add --patch`.
+    #[arg(short, long, action = ArgAction::SetTrue, conflicts_with_all = ["interactive", "all"])]
+    patch: bool,
+    /// Approve exactly the files read from stdin, one path relative to the repository root per line.
+    #[arg(long = "from-stdin", action = ArgAction::SetTrue, conflicts_with_all = ["interactive", "all", "patch"])]
+    from_stdin: bool,
+    /// Discard unreviewed changes without asking for confirmation.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    yes: bool,
+    /// Sign the approve commit [default: `cresca.sign` or `commit.gpgsign` git config].
+    #[arg(short = 'S', long, action = ArgAction::SetTrue)]
+    sign: bool,
+    /// Bypass the pre-commit and commit-msg hooks for the approve commit.
+    #[arg(long = "no-verify", action = ArgAction::SetTrue)]
+    no_verify: bool,
+    /// Attribute the approve commit to this author instead of the git identity, in the `Name <email>` format.
+    #[arg(long, value_name = "AUTHOR", value_parser = parse_author)]
+    author: Option<String>,
+    /// Create an empty approve commit if nothing is staged, so the approval is recorded in the history.
+    #[arg(long = "allow-empty", action = ArgAction::SetTrue)]
+    allow_empty: bool,
+}
+
+#[derive(Args)]
+struct CleanArgs {
+    /// Also delete review branches with unapproved remaining changes.
+    #[arg(long, action = ArgAction::SetTrue)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct CompleteArgs {
+    /// The words of the command line after `cresca`, ending with the word being completed.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+#[derive(Args)]
+struct CompletionsArgs {
+    /// The shell to generate the completion script for.
+    shell: Shell,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the value of a setting.
+    Get {
+        /// The key of the setting, with or without the `cresca.` prefix (e.g. `remote`).
+        key: String,
+    },
+    /// Set the value of a setting in the repository's git config.
+    Set {
+        /// The key of the setting, with or without the `cresca.` prefix (e.g. `remote`).
+        key: String,
+        /// The value to set.
+        value: String,
+    },
+    /// List the settings that are set.
+    List,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Limit the diff to these paths.
+    paths: Vec<String>,
+    /// Show the diff stat instead of the full diff.
+    #[arg(long, action = ArgAction::SetTrue)]
+    stat: bool,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// The file to write the patch to, or `-` for stdout.
+    #[arg(short, long, default_value = "-")]
+    output: String,
+}
+
+#[derive(Args)]
+struct FinalizeArgs {
+    /// Finalize even if unreviewed changes remain; only the approved changes are merged.
+    #[arg(long, action = ArgAction::SetTrue)]
+    partial: bool,
+    /// Delete the review branch after merging.
+    #[arg(long, action = ArgAction::SetTrue)]
+    delete: bool,
+}
+
+#[derive(Args)]
+struct PathsArgs {
+    /// The paths to mark, e.g. `.` or `src/*.rs`.
+    #[arg(required = true)]
+    paths: Vec<String>,
 }
 
 #[derive(Args)]
 struct ReviewArgs {
     /// The branch where the PR is planned to be merged into.
-    to: String,
+    /// If FROM is omitted, this is the development branch and `default_to` in the config is used instead.
+    #[cfg_attr(feature = "github", arg(required_unless_present_any = ["pr", "range"]))]
+    #[cfg_attr(not(feature = "github"), arg(required_unless_present = "range"))]
+    to: Option<String>,
     /// The development branch to be reviewed.
-    from: String,
+    from: Option<String>,
     /// Skip to this commit (auto-approve earlier commits).
     /// Use `git log --oneline <to>..<from>` to see available commits.
     #[arg(long = "skip-to")]
     skip_to: Option<String>,
     /// Stop at this commit (exclude later commits from review).
+    /// Must be at or after the `--skip-to` commit if both are given.
     /// Use `git log --oneline <to>..<from>` to see available commits.
     #[arg(long = "stop-at")]
     stop_at: Option<String>,
+    /// The remote to pull the branches from [default: `cresca.remote` git config, `remote` in the config file, or origin].
+    #[arg(long, env = "CRESCA_REMOTE")]
+    remote: Option<String>,
+    /// Do not pull the branches from the remote; use the local branch tips.
+    #[arg(long = "no-pull", action = ArgAction::SetTrue)]
+    no_pull: bool,
+    /// Give up on pulling or fetching a branch from the remote after this many seconds [default: no timeout].
+    #[arg(long, value_name = "SECONDS", conflicts_with = "no_pull")]
+    timeout: Option<u64>,
+    /// Retry a failed pull or fetch from the remote this many times, waiting a little longer before each retry.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        conflicts_with = "no_pull"
+    )]
+    retries: u32,
+    /// Strategy option passed to the squash merges (`git merge -X <option>`). Can be repeated.
+    /// Defaults to `theirs` when not specified.
+    #[arg(long = "strategy-option", short = 'X', value_name = "OPTION")]
+    strategy_options: Vec<String>,
+    /// Stop on merge conflicts instead of resolving them with the default `-X theirs`.
+    #[arg(long = "detect-conflicts", action = ArgAction::SetTrue)]
+    detect_conflicts: bool,
+    /// Ignore carriage returns at the end of lines, so files differing only in CRLF/LF line endings
+    /// are not shown in `cresca status` and `cresca diff` and do not conflict in the merges.
+    #[arg(long = "ignore-eol", action = ArgAction::SetTrue)]
+    ignore_eol: bool,
+    /// Review the commits one by one instead of squashing them into one diff. Each commit is
+    /// cherry-picked for review, and `cresca approve` commits the approved changes with its message
+    /// and moves on to the next commit, preserving the commit boundaries. Slower than the default,
+    /// and conflicts are resolved with `-X theirs` per commit, so `--detect-conflicts` cannot be used.
+    #[arg(long = "no-squash", action = ArgAction::SetTrue, conflicts_with = "detect_conflicts")]
+    no_squash: bool,
+    /// Print the git commands that would modify the repository without running them.
+    #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+    dry_run: bool,
+    /// List the commits to review, as trimmed by `--skip-to` and `--stop-at`, without modifying the repository.
+    /// The local branches are used as they are.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "dry_run")]
+    preview: bool,
+    /// Stash uncommitted changes, including untracked files, before starting the review.
+    /// The stash is kept for you to pop after the review.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "preview")]
+    autostash: bool,
+    /// The commit message for the commits auto-approved by `--skip-to` [default: "Auto-approve <N> commits up to <SKIP_TO>"].
+    #[arg(long = "auto-approve-message")]
+    auto_approve_message: Option<String>,
+    /// Sign the auto-approve commit [default: `cresca.sign` or `commit.gpgsign` git config].
+    #[arg(short = 'S', long, action = ArgAction::SetTrue)]
+    sign: bool,
+    /// Bypass the pre-commit and commit-msg hooks for the auto-approve commit.
+    #[arg(long = "no-verify", action = ArgAction::SetTrue)]
+    no_verify: bool,
+    /// Attribute the auto-approve commit to this author instead of the git identity, in the `Name <email>` format.
+    #[arg(long, value_name = "AUTHOR", value_parser = parse_author)]
+    author: Option<String>,
+    /// Recreate the review branch from scratch if it exists, discarding prior approvals.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "preview")]
+    force: bool,
+    /// Recreate the review branch without asking for confirmation.
+    #[arg(short, long, action = ArgAction::SetTrue, requires = "force")]
+    yes: bool,
+    /// Review branches with unrelated histories from an empty tree instead of failing.
+    #[arg(long = "allow-unrelated", action = ArgAction::SetTrue)]
+    allow_unrelated: bool,
+    /// Approve the files matching this glob automatically, e.g. `Cargo.lock`. Can be repeated.
+    /// Added to the globs of the `cresca.autoApprove` git config.
+    #[arg(long = "auto-approve", value_name = "GLOB")]
+    auto_approve: Vec<String>,
+    /// Leave the files matching this glob out of the review, without approving them. Can be repeated.
+    /// Added to the globs of the `cresca.exclude` git config.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Limit the review to this path, relative to the repository root. Can be repeated.
+    /// Changes outside of the paths are discarded from the working tree and left out of the status.
+    #[arg(long = "path", value_name = "PATH")]
+    paths: Vec<String>,
+    /// Prepare the review branch in a dedicated git worktree, keeping the current checkout on its branch
+    /// [default directory: `worktree_dir` in the config file, or a temporary directory].
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["preview", "autostash"])]
+    worktree: bool,
+    /// Review a GitHub pull request by number. The branches are resolved with the GitHub CLI (`gh`).
+    #[cfg(feature = "github")]
+    #[arg(long, conflicts_with_all = ["to", "from", "preview", "range"])]
+    pr: Option<u64>,
+    /// Review an explicit commit range instead of branches. The review branch is created off BASE,
+    /// and nothing is switched or pulled.
+    #[arg(long, value_name = "BASE..HEAD", conflicts_with_all = ["to", "from", "preview"])]
+    range: Option<String>,
+}
+
+#[derive(Args)]
+struct StatusArgs {
+    /// Print the status as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    json: bool,
+    /// Print the status in a single line (e.g. `develop: 2f +7 -2`).
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "json")]
+    short: bool,
+    /// Print the status with a template of `{field}` placeholders (e.g. `{file_count} files, {insertions}+/{deletions}-`).
+    #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["json", "short"])]
+    format: Option<String>,
+    /// The maximum number of remaining files to list.
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+    /// List all remaining files.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "limit")]
+    all: bool,
+    /// Show the insertions and deletions of each remaining file.
+    #[arg(long = "per-file", action = ArgAction::SetTrue)]
+    per_file: bool,
+    /// The order of the remaining files.
+    #[arg(long, value_enum, default_value_t = SortOrder::Name)]
+    sort: SortOrder,
+    /// Refresh the status until interrupted with Ctrl-C.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "json")]
+    watch: bool,
+    /// The number of seconds between refreshes in `--watch` mode.
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..), requires = "watch")]
+    interval: u64,
+    /// Show the status of this review branch instead of the current branch, without switching to it.
+    #[arg(long)]
+    branch: Option<String>,
+    /// Compare against this commit instead of the tip of the development branch, e.g. the PR head when the review started.
+    #[arg(long, value_name = "COMMIT")]
+    against: Option<String>,
+}
+
+#[derive(Args)]
+struct SwitchArgs {
+    /// The branch where the PR is planned to be merged into.
+    /// If FROM is omitted, this is the development branch and `default_to` in the config is used instead.
+    to: String,
+    /// The development branch being reviewed.
+    from: Option<String>,
+}
+
+#[derive(Args)]
+struct UnapproveArgs {
+    /// The paths of the files to unapprove, e.g. `src/main.rs`.
+    #[arg(required = true)]
+    paths: Vec<String>,
+    /// Sign the rewritten approve commit [default: `cresca.sign` or `commit.gpgsign` git config].
+    #[arg(short = 'S', long, action = ArgAction::SetTrue)]
+    sign: bool,
+    /// Bypass the pre-commit and commit-msg hooks for the rewritten approve commit.
+    #[arg(long = "no-verify", action = ArgAction::SetTrue)]
+    no_verify: bool,
+    /// Attribute the rewritten approve commit to this author instead of the git identity, in the `Name <email>` format.
+    #[arg(long, value_name = "AUTHOR", value_parser = parse_author)]
+    author: Option<String>,
+}
+
+/// Order of the remaining files in `cresca status`
+#[derive(Clone, Copy, ValueEnum)]
+enum SortOrder {
+    /// Sort alphabetically by path.
+    Name,
+    /// Sort by the number of changed lines, largest first.
+    Size,
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    // `colored` already disables color when stdout is not a terminal (unless `CLICOLOR_FORCE` is set)
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if cli.no_color || no_color_env {
+        colored::control::set_override(false);
+    }
+
+    if let Some(git_binary) = &cli.git_binary {
+        set_git_binary(git_binary);
+    }
+
+    if let Err(e) = run(&cli) {
+        let git_error = match &e {
+            CrescaError::Git(e) | CrescaError::SigningFailed(e) => Some(e),
+            _ => None,
+        };
+        match &cli.command {
+            _ if matches!(e, CrescaError::AlreadyReported) => {}
+            // Scripts reading `status --json` get every error as JSON too
+            Commands::Status(args) if args.json => {
+                let mut error = serde_json::json!({ "error": e.to_string() });
+                if let Some(git_error) = git_error {
+                    error["git_error"] = git_error.stderr.trim().into();
+                }
+                eprintln!("{}", error);
+            }
+            _ => {
+                eprintln!("{}: {}", msg(Id::Error).red().bold(), e);
+                if let Some(git_error) = git_error {
+                    eprintln!("{}", msg(Id::OriginalGitError));
+                    eprintln!("\t{}", git_error.stderr);
+                }
+            }
+        }
+        // Interrupts exit like a process killed by SIGINT, and a missing git like a shell command not found
+        exit(match e {
+            CrescaError::Interrupted | CrescaError::ReviewInterrupted(_) => 130,
+            CrescaError::GitNotFound(_) => 127,
+            _ => 1,
+        });
+    }
+}
+
+/// Run the given command
+fn run(cli: &Cli) -> Result<(), CrescaError> {
+    let git = &ProcessGitRunner;
+    if let Some(path) = &cli.log_file {
+        set_log_file(path).map_err(|e| CrescaError::LogFile {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+    }
+
+    // Completion scripts do not depend on the repository, completion candidates are simply
+    // empty outside of it, and the doctor checks it itself
+    if !matches!(
+        cli.command,
+        Commands::Complete(_) | Commands::Completions(_) | Commands::Doctor
+    ) && !is_git_repo(git, cli.verbose_level())?
+    {
+        return Err(CrescaError::NotGitRepository);
+    }
+
+    let config = load_config(git, cli.verbose_level())?;
+    let prefix = config.prefix.as_deref().unwrap_or(DEFAULT_PREFIX);
+
+    // Resolve the current branch once for the commands working on the current review branch.
+    // If it cannot be resolved, the lookups below resolve it again to report the error.
+    let current_branch = if matches!(
+        cli.command,
+        Commands::Abort
+            | Commands::Add(_)
+            | Commands::Approve(_)
+            | Commands::Continue
+            | Commands::Diff(_)
+            | Commands::Export(_)
+            | Commands::Finalize(_)
+            | Commands::Log
+            | Commands::Reset(_)
+            | Commands::Status(_)
+            | Commands::Unapprove(_)
+            | Commands::Undo
+    ) {
+        get_current_branch(git, cli.verbose_level()).ok()
+    } else {
+        None
+    };
+    let current_branch = current_branch.as_deref();
+
     match &cli.command {
-        Commands::Approve => {
-            if is_review_branch(cli.verbose) {
-                let res = approve_changes(cli.verbose);
-                match res {
-                    Err(_) => {
-                        println!("There are no reviewed changes to approve. Ending the review.",)
+        Commands::Abort => {
+            if let Some((to_branch, _)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                let branch = abort_review(
+                    git,
+                    &to_branch,
+                    &configured_remote(&config),
+                    cli.verbose_level(),
+                )?;
+                if !cli.quiet {
+                    println!("{}", fill(Id::ReviewAborted, &[&branch.green()]));
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Add(args) => {
+            if !is_review_branch(git, prefix, current_branch, cli.verbose_level())? {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+            let count = stage_paths(git, &args.paths, cli.verbose_level())?;
+            if !cli.quiet {
+                print_staged_count(count);
+            }
+        }
+        Commands::Approve(args) => {
+            if is_review_branch(git, prefix, current_branch, cli.verbose_level())? {
+                if args.all {
+                    stage_all_changes(git, cli.verbose_level())?;
+                } else if args.patch {
+                    stage_hunks_interactively(git, cli.verbose_level())?;
+                } else if args.interactive || args.from_stdin {
+                    if let Some((to_branch, from_branch)) =
+                        get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+                    {
+                        let status = get_review_status(
+                            git,
+                            current_branch.unwrap_or("HEAD"),
+                            &to_branch,
+                            &from_branch,
+                            None,
+                            cli.verbose_level(),
+                        )?;
+                        if args.from_stdin {
+                            let files: Vec<String> = io::stdin()
+                                .lines()
+                                .map_while(Result::ok)
+                                .filter(|line| !line.trim().is_empty())
+                                .collect();
+                            let skipped = stage_listed_files(
+                                git,
+                                &files,
+                                &status.files,
+                                cli.verbose_level(),
+                            )?;
+                            for file in skipped {
+                                eprintln!(
+                                    "{}: {}",
+                                    msg(Id::Warning).yellow().bold(),
+                                    fill(Id::SkippingFile, &[&file])
+                                );
+                            }
+                        } else {
+                            stage_files_interactively(git, &status.files, cli.verbose_level())?;
+                        }
                     }
-                    Ok(_) => println!("Reviewed changes were approved successfully.",),
+                }
+                if !args.yes && !confirm_discarding_changes(git, cli.verbose_level())? {
+                    if !cli.quiet {
+                        println!("{}", msg(Id::ApprovalCancelled));
+                    }
+                } else {
+                    // The configured message would replace the messages kept by a no-squash review
+                    let default_message = match current_review_commit(git, cli.verbose_level())? {
+                        Some(_) => None,
+                        None => config.approve_message.as_deref(),
+                    };
+                    let sign = args.sign || signing_enabled(git, cli.verbose_level());
+                    let approved = approve_changes(
+                        git,
+                        args.message.as_deref().or(default_message),
+                        args.author.as_deref(),
+                        sign,
+                        args.no_verify,
+                        args.allow_empty,
+                        cli.verbose_level(),
+                    )?;
+                    let next_commit = review_next_commit(
+                        git,
+                        args.author.as_deref(),
+                        sign,
+                        args.no_verify,
+                        cli.verbose_level(),
+                    )?;
+                    if !cli.quiet {
+                        if approved {
+                            println!("{}", msg(Id::ChangesApproved));
+                        }
+                        match next_commit {
+                            Some(commit) => {
+                                println!("{}", fill(Id::ReviewingCommit, &[&commit.green()]))
+                            }
+                            None if !approved => println!("{}", msg(Id::NothingToApprove)),
+                            None => {}
+                        }
+                    }
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Clean(args) => {
+            let result = clean_review_branches(git, prefix, args.force, cli.verbose_level())?;
+            if cli.quiet {
+                return Ok(());
+            }
+            for branch in &result.deleted {
+                println!("{}", fill(Id::BranchDeleted, &[&branch.green()]));
+            }
+            for branch in &result.skipped {
+                println!(
+                    "{}",
+                    fill(Id::BranchSkipped, &[&branch.yellow(), &"--force".green()])
+                );
+            }
+            println!("{}", fill(Id::BranchesDeleted, &[&result.deleted.len()]));
+        }
+        Commands::Complete(args) => {
+            for candidate in complete(git, &args.words, &config, cli.verbose_level()) {
+                println!("{}", candidate);
+            }
+        }
+        Commands::Completions(args) => print!("{}", completion_script(args.shell)),
+        Commands::Config(ConfigCommand::Get { key }) => {
+            let key = resolve_git_config_key(key)?;
+            let values = try_get_config_all(git, key, cli.verbose_level())?;
+            if values.is_empty() {
+                // Like `git config --get`, an unset key only fails with the exit status
+                return Err(CrescaError::AlreadyReported);
+            }
+            for value in values {
+                println!("{}", value);
+            }
+        }
+        Commands::Config(ConfigCommand::Set { key, value }) => {
+            let key = resolve_git_config_key(key)?;
+            set_config(git, key, value, cli.verbose_level())?;
+        }
+        Commands::Config(ConfigCommand::List) => {
+            for (key, _) in GIT_CONFIG_KEYS {
+                for value in try_get_config_all(git, key, cli.verbose_level())? {
+                    println!("{}={}", key, value);
+                }
+            }
+        }
+        Commands::Continue => {
+            if is_review_branch(git, prefix, current_branch, cli.verbose_level())? {
+                continue_review(git, cli.verbose_level())?;
+                print_review_prepared(git, cli.quiet, cli.verbose_level())?;
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Diff(args) => {
+            if let Some((_, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                // Default to the paths the review is limited to
+                let paths = if args.paths.is_empty() {
+                    let review_branch = current_branch.map_or_else(
+                        || get_current_branch(git, cli.verbose_level()),
+                        |branch| Ok(branch.to_string()),
+                    )?;
+                    top_pathspecs(&get_config_all(
+                        git,
+                        &format!("cresca.{}.path", review_branch),
+                        cli.verbose_level(),
+                    ))
+                } else {
+                    args.paths.clone()
                 };
+                write_review_diff(
+                    git,
+                    &from_branch,
+                    &paths,
+                    args.stat,
+                    &mut io::stdout().lock(),
+                    cli.verbose_level(),
+                )?;
             } else {
-                eprintln!(
-                    "{}: Not on a review branch; run `{}` to prepare a review branch.",
-                    "error".red().bold(),
-                    "cresca review".green()
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Doctor => print_doctor(git, &config, prefix, cli.verbose_level()),
+        Commands::Export(args) => {
+            if let Some((to_branch, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                let patch = get_review_patch(git, &to_branch, &from_branch, cli.verbose_level())?;
+                if patch.is_empty() {
+                    return Err(CrescaError::NothingToExport);
+                }
+                if args.output == "-" {
+                    print!("{}", patch);
+                } else {
+                    std::fs::write(&args.output, patch).map_err(|e| CrescaError::WriteFailed {
+                        path: args.output.clone(),
+                        message: e.to_string(),
+                    })?;
+                    if !cli.quiet {
+                        println!("{}", fill(Id::ChangesExported, &[&args.output.green()]));
+                    }
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Finalize(args) => {
+            if let Some((to_branch, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                finalize_review(
+                    git,
+                    &to_branch,
+                    &from_branch,
+                    args.partial,
+                    args.delete,
+                    cli.verbose_level(),
+                )?;
+                if !cli.quiet {
+                    println!("{}", fill(Id::ChangesMerged, &[&to_branch.green()]));
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::List => {
+            let branches = list_review_branches(git, prefix, cli.verbose_level())?;
+            if branches.is_empty() && !cli.quiet {
+                println!("{}", msg(Id::NoReviewBranches));
+            }
+            for branch in branches {
+                println!(
+                    "{} {}  {} <- {}  ({})",
+                    if branch.is_current { "*" } else { " " },
+                    branch.name.green(),
+                    branch.to_branch,
+                    branch.pull_request_head.unwrap_or(branch.from_branch),
+                    branch.last_commit_date
                 );
-                exit(1);
+            }
+        }
+        Commands::Log => {
+            if let Some((to_branch, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                let log = get_review_log(git, &to_branch, &from_branch, cli.verbose_level())?;
+                println!("{}:", msg(Id::Approved).green().bold());
+                print_commits(&log.approved);
+                println!("{} ({}):", msg(Id::Remaining).yellow().bold(), from_branch);
+                print_commits(&log.remaining);
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Reset(args) => {
+            if !is_review_branch(git, prefix, current_branch, cli.verbose_level())? {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+            let count = unstage_paths(git, &args.paths, cli.verbose_level())?;
+            if !cli.quiet {
+                print_staged_count(count);
             }
         }
         Commands::Review(args) => {
-            if !is_clean(cli.verbose) {
-                eprintln!("{}: Uncommitted changes found. Please commit or stash them before starting review.", "error".red().bold());
-                exit(1);
+            #[cfg(feature = "github")]
+            let pull_request = args
+                .pr
+                .map(|number| {
+                    let pr = get_pull_request(number, cli.verbose_level())?;
+                    Ok::<_, CrescaError>((number, pr.base_ref_name, pr.head_ref_name))
+                })
+                .transpose()?;
+            #[cfg(not(feature = "github"))]
+            let pull_request: Option<(u64, String, String)> = None;
+
+            let range = args
+                .range
+                .as_deref()
+                .map(|range| parse_range(git, range, cli.verbose_level()))
+                .transpose()?;
+
+            let (to_branch, from_branch) = match (range, &pull_request) {
+                (Some((base, head)), _) => (base, head),
+                (None, Some((_, to, from))) => (to.clone(), from.clone()),
+                (None, None) => {
+                    resolve_branches(args.to.as_deref(), args.from.as_deref(), &config)?
+                }
+            };
+
+            if args.preview {
+                let commits = preview_review(
+                    git,
+                    &to_branch,
+                    &from_branch,
+                    args.skip_to.as_deref(),
+                    args.stop_at.as_deref(),
+                    cli.verbose_level(),
+                )?;
+                print_preview(&to_branch, &from_branch, &commits);
+                return Ok(());
+            }
+
+            if args.force
+                && !args.yes
+                && !args.dry_run
+                && !confirm_recreating_review_branch(
+                    git,
+                    &review_branch_name(prefix, &to_branch, &from_branch),
+                    cli.verbose_level(),
+                )
+            {
+                if !cli.quiet {
+                    println!("{}", msg(Id::ReviewCancelled));
+                }
+                return Ok(());
+            }
+
+            // The current checkout is not touched when reviewing in a worktree
+            if !args.worktree && !is_clean(git, cli.verbose_level())? {
+                if !args.autostash {
+                    return Err(CrescaError::DirtyWorkingTree);
+                }
+                let branch = get_current_branch(git, cli.verbose_level())?;
+                stash_changes(
+                    git,
+                    &format!("cresca autostash on {}", branch),
+                    args.dry_run,
+                    cli.verbose_level(),
+                )?;
+                if !cli.quiet {
+                    println!(
+                        "{}",
+                        fill(
+                            Id::ChangesStashed,
+                            &[&"git stash pop".green(), &branch.green()]
+                        )
+                    );
+                }
             }
 
-            prepare_review_branch(
-                &args.to,
-                &args.from,
-                args.skip_to.as_deref(),
-                args.stop_at.as_deref(),
-                cli.verbose,
+            let remote = args
+                .remote
+                .clone()
+                .or_else(|| config.remote.clone())
+                .unwrap_or_else(|| "origin".to_string());
+
+            let mut auto_approve = get_config_all(git, "cresca.autoApprove", cli.verbose_level());
+            auto_approve.extend(args.auto_approve.iter().cloned());
+            let mut exclude = get_config_all(git, "cresca.exclude", cli.verbose_level());
+            exclude.extend(args.exclude.iter().cloned());
+
+            let worktree = args
+                .worktree
+                .then(|| {
+                    review_worktree_path(
+                        git,
+                        &review_branch_name(prefix, &to_branch, &from_branch),
+                        config.worktree_dir.as_deref(),
+                        cli.verbose_level(),
+                    )
+                })
+                .transpose()?;
+
+            // Ctrl-C stops preparing the review branch between its steps, and the repository is restored here
+            let original = if args.dry_run {
+                None
+            } else {
+                let original = original_checkout(git, args.worktree, cli.verbose_level())?;
+                ctrlc::set_handler(request_interrupt)
+                    .map_err(|e| CrescaError::SignalHandler(e.to_string()))?;
+                original
+            };
+            let prepared = prepare_review_branch(
+                git,
+                &to_branch,
+                &from_branch,
+                &ReviewOptions {
+                    skip_to: args.skip_to.as_deref(),
+                    stop_at: args.stop_at.as_deref(),
+                    remote: &remote,
+                    no_pull: args.no_pull,
+                    timeout: args.timeout.map(Duration::from_secs),
+                    retries: args.retries,
+                    // Raw git output already shows what is going on in verbose mode
+                    progress: !cli.quiet && cli.verbose_level() == 0 && io::stderr().is_terminal(),
+                    auto_approve_message: args.auto_approve_message.as_deref(),
+                    dry_run: args.dry_run,
+                    prefix,
+                    strategy_options: &args.strategy_options,
+                    detect_conflicts: args.detect_conflicts,
+                    ignore_eol: args.ignore_eol,
+                    no_squash: args.no_squash,
+                    pull_request: pull_request.as_ref().map(|(number, _, _)| *number),
+                    range: args.range.is_some(),
+                    sign: args.sign || signing_enabled(git, cli.verbose_level()),
+                    no_verify: args.no_verify,
+                    author: args.author.as_deref(),
+                    force: args.force,
+                    allow_unrelated: args.allow_unrelated,
+                    auto_approve: &auto_approve,
+                    exclude: &exclude,
+                    paths: &args.paths,
+                    worktree: worktree.as_deref(),
+                },
+                cli.verbose_level(),
             );
-            if is_clean(cli.verbose) {
-                println!("Review branch prepared successfully. However, it seems like there are no unreviewed changes.");
+            let prepared = match prepared {
+                Err(_) if interrupt_requested() => {
+                    return Err(restore_interrupted_review(
+                        git,
+                        &review_branch_name(prefix, &to_branch, &from_branch),
+                        original,
+                        cli.verbose_level(),
+                    ));
+                }
+                prepared => prepared?,
+            };
+            if !prepared {
+                if !cli.quiet {
+                    println!(
+                        "{}",
+                        fill(
+                            Id::AlreadyMerged,
+                            &[&from_branch.green(), &to_branch.green()]
+                        )
+                    );
+                }
+            } else if args.dry_run {
+                println!("{}", msg(Id::DryRunFinished));
             } else {
-                println!("Review branch prepared successfully. Stage the changes you have reviewed and run `{}` to approve them.", "cresca approve".green());
+                print_review_prepared(git, cli.quiet, cli.verbose_level())?;
+                if let Some(path) = &worktree {
+                    // Printed even in quiet mode, since the review cannot be found otherwise
+                    println!(
+                        "{}",
+                        fill(
+                            Id::ReviewInWorktree,
+                            &[
+                                &path.display().to_string().green(),
+                                &format!("cd {}", path.display()).green()
+                            ]
+                        )
+                    );
+                }
             }
         }
-        Commands::Status => {
-            if let Some((_, from_branch)) = get_review_branch_info(cli.verbose) {
-                let status = get_review_status(&from_branch, cli.verbose);
-                println!("📋 Review status:");
-                println!(
-                    "  Remaining diff to {}: {} file(s), {} insertion(s), {} deletion(s)",
-                    status.from_branch.green(),
-                    status.file_count.to_string().yellow(),
-                    format!("+{}", status.insertions).green(),
-                    format!("-{}", status.deletions).red()
-                );
-                if !status.files.is_empty() {
-                    const MAX_FILES: usize = 10;
-                    println!("  Files remaining:");
-                    for file in status.files.iter().take(MAX_FILES) {
-                        println!("    - {}", file);
+        Commands::Stats => {
+            let branches = list_review_branches(git, prefix, cli.verbose_level())?;
+            if branches.is_empty() {
+                if !cli.quiet {
+                    println!("{}", msg(Id::NoReviewBranches));
+                }
+                return Ok(());
+            }
+            let mut rows = Vec::new();
+            for branch in &branches {
+                match get_review_status(
+                    git,
+                    &branch.name,
+                    &branch.to_branch,
+                    &branch.from_branch,
+                    None,
+                    cli.verbose_level(),
+                ) {
+                    Ok(status) => rows.push((&branch.name, status)),
+                    Err(e) => eprintln!(
+                        "{}: {}",
+                        msg(Id::Warning).yellow().bold(),
+                        fill(Id::StatsSkipped, &[&branch.name, &e])
+                    ),
+                }
+            }
+            print_stats(&rows);
+        }
+        Commands::Status(args) => {
+            let review_info = match &args.branch {
+                Some(branch) => Some(
+                    rev_parse(git, branch, cli.verbose_level())
+                        .ok()
+                        .and_then(|_| {
+                            get_branch_review_info(git, prefix, branch, cli.verbose_level())
+                        })
+                        .ok_or_else(|| CrescaError::UnknownReviewBranch(branch.clone()))?,
+                ),
+                None => get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?,
+            };
+            let review_branch = args.branch.as_deref().or(current_branch).unwrap_or("HEAD");
+            if let Some((to_branch, from_branch)) = review_info {
+                if rev_parse(git, &from_branch, cli.verbose_level()).is_err() {
+                    if args.json {
+                        // The JSON errors are for scripts, so they stay in English whatever the language
+                        eprintln!(
+                            "{}",
+                            serde_json::json!({
+                                "error": format!("Branch {} no longer exists", from_branch)
+                            })
+                        );
+                    } else if args.short {
+                        eprintln!("{}", fill(Id::ShortFromBranchGone, &[&from_branch]));
+                    } else {
+                        return Err(CrescaError::FromBranchGone(from_branch));
                     }
-                    if status.files.len() > MAX_FILES {
+                    return Err(CrescaError::AlreadyReported);
+                }
+                let no_emoji_env =
+                    std::env::var_os("CRESCA_NO_EMOJI").is_some_and(|value| !value.is_empty());
+                let emoji = !cli.no_emoji && !no_emoji_env && io::stdout().is_terminal();
+                let render = || -> Result<(), CrescaError> {
+                    let mut status = get_review_status(
+                        git,
+                        review_branch,
+                        &to_branch,
+                        &from_branch,
+                        args.against.as_deref(),
+                        cli.verbose_level(),
+                    )?;
+                    sort_files(&mut status, args.sort);
+                    if args.json {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&status)
+                                .expect("Failed to serialize review status")
+                        );
+                    } else if let Some(template) = &args.format {
+                        println!("{}", status.format(template)?);
+                    } else if args.short {
                         println!(
-                            "    ... and {} more file(s)",
-                            status.files.len() - MAX_FILES
+                            "{}: {}f {} {}",
+                            status.from_branch,
+                            status.file_count,
+                            format!("+{}", status.insertions).green(),
+                            format!("-{}", status.deletions).red()
+                        );
+                    } else if !cli.quiet {
+                        print_status(
+                            &status,
+                            (!args.all).then_some(args.limit),
+                            args.per_file,
+                            emoji,
                         );
                     }
+                    Ok(())
+                };
+                if args.watch {
+                    watch(args.interval, render)?;
+                } else {
+                    render()?;
                 }
-            } else {
+            } else if args.json {
+                // The JSON errors are for scripts, so they stay in English whatever the language
                 eprintln!(
-                    "{}: Not on a review branch; run `{}` to prepare a review branch.",
-                    "error".red().bold(),
-                    "cresca review".green()
+                    "{}",
+                    serde_json::json!({
+                        "error": if is_detached_head(git, cli.verbose_level())? {
+                            "Detached HEAD"
+                        } else {
+                            "Not on a review branch"
+                        }
+                    })
+                );
+                return Err(CrescaError::AlreadyReported);
+            } else if args.short {
+                if is_detached_head(git, cli.verbose_level())? {
+                    eprintln!("{}", msg(Id::ShortDetachedHead));
+                } else {
+                    eprintln!("{}", msg(Id::ShortNotOnReviewBranch));
+                }
+                return Err(CrescaError::AlreadyReported);
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Switch(args) => {
+            let (to_branch, from_branch) =
+                resolve_branches(Some(&args.to), args.from.as_deref(), &config)?;
+            let review_branch =
+                switch_review_branch(git, &to_branch, &from_branch, prefix, cli.verbose_level())?;
+            if !cli.quiet {
+                println!(
+                    "{}",
+                    fill(Id::SwitchedToReviewBranch, &[&review_branch.green()])
                 );
-                exit(1);
             }
         }
+        Commands::Unapprove(args) => {
+            if let Some((_, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                let files = unapprove_paths(
+                    git,
+                    &from_branch,
+                    &args.paths,
+                    args.author.as_deref(),
+                    args.sign || signing_enabled(git, cli.verbose_level()),
+                    args.no_verify,
+                    cli.verbose_level(),
+                )?;
+                if !cli.quiet {
+                    for file in files {
+                        println!("{}", fill(Id::FileUnapproved, &[&file.yellow()]));
+                    }
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+        Commands::Undo => {
+            if let Some((_, from_branch)) =
+                get_review_branch_info(git, prefix, current_branch, cli.verbose_level())?
+            {
+                let commit = undo_approval(git, &from_branch, cli.verbose_level())?;
+                if !cli.quiet {
+                    println!("{}", fill(Id::ApprovalUndone, &[&commit.yellow()]));
+                }
+            } else {
+                return Err(not_on_review_branch(git, cli.verbose_level()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the target branch and the development branch from the branch arguments
+///
+/// If only one branch is given, it is the development branch and `default_to` in the config is the target branch.
+///
+/// # Arguments
+///
+/// * `to` - The first branch argument.
+/// * `from` - The second branch argument.
+/// * `config` - The loaded configuration.
+fn resolve_branches(
+    to: Option<&str>,
+    from: Option<&str>,
+    config: &Config,
+) -> Result<(String, String), CrescaError> {
+    match (to, from, &config.default_to) {
+        (Some(to), Some(from), _) => Ok((to.to_string(), from.to_string())),
+        (Some(from), None, Some(default_to)) => Ok((default_to.clone(), from.to_string())),
+        _ => Err(CrescaError::MissingFromBranch),
+    }
+}
+
+/// Parse the `--author` argument, rejecting values not in the `Name <email>` format
+///
+/// # Arguments
+///
+/// * `author` - The author given on the command line.
+fn parse_author(author: &str) -> Result<String, String> {
+    validate_author(author)
+        .map(|_| author.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a `<base>..<head>` range into the abbreviated commit hashes of both ends
+///
+/// The ends are resolved so that the review does not move with them, e.g. for `HEAD~3..HEAD`.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `range` - The commit range.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn parse_range(
+    git: &impl GitRunner,
+    range: &str,
+    verbose: u8,
+) -> Result<(String, String), CrescaError> {
+    let Some((base, head)) = range
+        .split_once("..")
+        .filter(|(base, head)| !base.is_empty() && !head.is_empty() && !head.starts_with('.'))
+    else {
+        return Err(CrescaError::InvalidRange(range.to_string()));
+    };
+    let resolve = |revision: &str| {
+        rev_parse(git, revision, verbose)
+            // Abbreviated hashes keep the review branch name short
+            .map(|hash| hash[..12].to_string())
+            .map_err(|_| CrescaError::RangeNotACommit(revision.to_string()))
+    };
+    Ok((resolve(base)?, resolve(head)?))
+}
+
+/// Print that the review branch is prepared, with a hint for the next step
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `quiet` - Whether to suppress the message.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn print_review_prepared(
+    git: &impl GitRunner,
+    quiet: bool,
+    verbose: u8,
+) -> Result<(), CrescaError> {
+    if quiet {
+        return Ok(());
+    }
+    if is_clean(git, verbose)? {
+        println!("{}", msg(Id::ReviewPreparedEmpty));
+    } else {
+        println!("{}", fill(Id::ReviewPrepared, &[&"cresca approve".green()]));
+    }
+    Ok(())
+}
+
+/// Print the review status in a human-readable format, listing at most `limit` files (with their line counts if `per_file` is set)
+fn print_status(status: &ReviewStatus, limit: Option<usize>, per_file: bool, emoji: bool) {
+    if emoji {
+        println!("📋 {}", msg(Id::ReviewStatus));
+    } else {
+        println!("{}", msg(Id::ReviewStatus));
+    }
+    let binary_files = if status.binary_files > 0 {
+        fill(Id::BinaryFiles, &[&status.binary_files])
+    } else {
+        String::new()
+    };
+    println!(
+        "  {}",
+        fill(
+            Id::RemainingDiff,
+            &[
+                &status.from_branch.green(),
+                &fill_plural(
+                    status.file_count,
+                    Id::FileCount,
+                    Id::FilesCount,
+                    &[&status.file_count.to_string().yellow()]
+                ),
+                &binary_files,
+                &fill_plural(
+                    status.insertions,
+                    Id::InsertionCount,
+                    Id::InsertionsCount,
+                    &[&format!("+{}", status.insertions).green()]
+                ),
+                &fill_plural(
+                    status.deletions,
+                    Id::DeletionCount,
+                    Id::DeletionsCount,
+                    &[&format!("-{}", status.deletions).red()]
+                )
+            ]
+        )
+    );
+    println!(
+        "  {}",
+        fill(
+            Id::Progress,
+            &[
+                &format!("{}%", status.reviewed_percent).cyan(),
+                &status.reviewed_lines(),
+                &status.total_lines()
+            ]
+        )
+    );
+    if !status.files.is_empty() {
+        let max_files = limit.unwrap_or(status.files.len());
+        println!("  {}", msg(Id::FilesRemaining));
+        // Paths are shortened to fit in the terminal, so each file stays on one line
+        let terminal_width =
+            terminal_size_of(io::stdout()).map(|(Width(width), _)| usize::from(width));
+        if per_file {
+            let shown = &status.file_stats[..max_files.min(status.file_stats.len())];
+            let stats_width = shown
+                .iter()
+                .map(|stat| {
+                    if stat.binary {
+                        msg(Id::Binary).width()
+                    } else {
+                        format!("+{}/-{}", stat.insertions, stat.deletions).len()
+                    }
+                })
+                .max()
+                .unwrap_or(0);
+            let paths: Vec<String> = shown
+                .iter()
+                .map(|stat| match terminal_width {
+                    Some(width) => truncate_path(&stat.path, width.saturating_sub(8 + stats_width)),
+                    None => stat.path.clone(),
+                })
+                .collect();
+            let width = paths.iter().map(|path| path.width()).max().unwrap_or(0);
+            for (stat, path) in shown.iter().zip(&paths) {
+                // Padded by display width, as `{:<width$}` counts wide characters as one column
+                let path = format!("{}{}", path, " ".repeat(width - path.width()));
+                if stat.binary {
+                    println!("    - {}  {}", path, msg(Id::Binary));
+                } else {
+                    println!(
+                        "    - {}  {}/{}",
+                        path,
+                        format!("+{}", stat.insertions).green(),
+                        format!("-{}", stat.deletions).red()
+                    );
+                }
+            }
+        } else {
+            for file in status.files.iter().take(max_files) {
+                match terminal_width {
+                    Some(width) => {
+                        println!("    - {}", truncate_path(file, width.saturating_sub(6)))
+                    }
+                    None => println!("    - {}", file),
+                }
+            }
+        }
+        let more_files = status.files.len().saturating_sub(max_files);
+        if more_files > 0 {
+            println!(
+                "    {}",
+                fill_plural(more_files, Id::MoreFile, Id::MoreFiles, &[&more_files])
+            );
+        }
+    }
+}
+
+/// Get the remote set by `CRESCA_REMOTE` or the config, falling back to origin
+///
+/// # Arguments
+///
+/// * `config` - The loaded configuration.
+fn configured_remote(config: &Config) -> String {
+    std::env::var("CRESCA_REMOTE")
+        .ok()
+        .or_else(|| config.remote.clone())
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+/// Print a table of the remaining diff and the progress of review branches
+///
+/// # Arguments
+///
+/// * `rows` - The review branches and their review status.
+fn print_stats(rows: &[(&String, ReviewStatus)]) {
+    let header = [
+        msg(Id::ColumnBranch).to_string(),
+        msg(Id::ColumnFrom).to_string(),
+        msg(Id::ColumnFiles).to_string(),
+        "+/-".to_string(),
+        msg(Id::ColumnReviewed).to_string(),
+    ];
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|(name, status)| {
+            [
+                name.to_string(),
+                status.from_branch.clone(),
+                status.file_count.to_string(),
+                format!("+{}/-{}", status.insertions, status.deletions),
+                format!("{}%", status.reviewed_percent),
+            ]
+        })
+        .collect();
+    let mut widths = header.clone().map(|cell| cell.width());
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    // Padded by display width, as `{:<width$}` counts wide characters as one column
+    let left = |cell: &str, width: usize| format!("{}{}", cell, " ".repeat(width - cell.width()));
+    let right = |cell: &str, width: usize| format!("{}{}", " ".repeat(width - cell.width()), cell);
+    println!(
+        "{}  {}  {}  {}  {}",
+        left(&header[0], widths[0]),
+        left(&header[1], widths[1]),
+        right(&header[2], widths[2]),
+        right(&header[3], widths[3]),
+        right(&header[4], widths[4])
+    );
+    for [name, from, files, lines, percent] in &cells {
+        println!(
+            "{}  {}  {}  {}  {}",
+            left(name, widths[0]).green(),
+            left(from, widths[1]),
+            right(files, widths[2]),
+            right(lines, widths[3]),
+            right(percent, widths[4]).cyan()
+        );
+    }
+}
+
+/// Shorten a path to fit in a width by replacing its middle directories with an ellipsis
+///
+/// The file name is kept as long as possible, e.g. `src/…/really_long_name.rs`.
+///
+/// # Arguments
+///
+/// * `path` - The path to shorten.
+/// * `width` - The maximum display width, in terminal columns. Wide characters such as CJK take two.
+///
+/// # Returns
+///
+/// The path as it is if it fits, or the shortened path otherwise
+fn truncate_path(path: &str, width: usize) -> String {
+    if path.width() <= width {
+        return path.to_string();
+    }
+    let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+    // Not even the file name fits, so as much of its end as fits next to the ellipsis is kept
+    if name.width() + 2 > width {
+        let mut tail_width = 0;
+        let start = name
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| {
+                tail_width += c.width().unwrap_or(0);
+                tail_width < width
+            })
+            .last()
+            .map_or(name.len(), |(index, _)| index);
+        return format!("…{}", &name[start..]);
+    }
+    // Keep as many leading directories as fit
+    let mut head = String::new();
+    for component in dir.split('/') {
+        if head.width() + component.width() + name.width() + 3 > width {
+            break;
+        }
+        head.push_str(component);
+        head.push('/');
+    }
+    format!("{}…/{}", head, name)
+}
+
+/// Print a checklist of the environment and the repository state
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `config` - The loaded configuration.
+/// * `prefix` - The prefix of review branch names.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn print_doctor(git: &impl GitRunner, config: &Config, prefix: &str, verbose: u8) {
+    let check = |ok: bool, message: String| {
+        if ok {
+            println!("{} {}", "✓".green(), message);
+        } else {
+            println!("{} {}", "✗".red(), message);
+        }
+    };
+
+    match run_git_command(git, "get git version", &["--version"], verbose) {
+        Ok(output) => check(
+            true,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Err(e) => check(false, fill(Id::CheckGitVersionUnknown, &[&e.stderr.trim()])),
+    }
+
+    if !is_git_repo(git, verbose).unwrap_or(false) {
+        check(false, msg(Id::CheckNotInWorkTree).to_string());
+        return;
+    }
+    check(true, msg(Id::CheckInWorkTree).to_string());
+
+    match get_current_branch(git, verbose) {
+        Ok(_) if is_detached_head(git, verbose).unwrap_or(false) => {
+            check(false, msg(Id::CheckHeadDetached).to_string())
+        }
+        Ok(branch) => check(true, fill(Id::CheckCurrentBranch, &[&branch])),
+        Err(e) => check(
+            false,
+            fill(Id::CheckCurrentBranchUnknown, &[&e.stderr.trim()]),
+        ),
+    }
+
+    match get_review_branch_info(git, prefix, None, verbose) {
+        Ok(Some((to_branch, from_branch))) => check(
+            true,
+            fill(Id::CheckOnReviewBranch, &[&from_branch, &to_branch]),
+        ),
+        _ => check(false, msg(Id::CheckNotOnReviewBranch).to_string()),
+    }
+
+    match is_clean(git, verbose) {
+        Ok(true) => check(true, msg(Id::CheckClean).to_string()),
+        _ => check(false, msg(Id::CheckDirty).to_string()),
+    }
+
+    let remote = configured_remote(config);
+    match remote_exists(git, &remote, verbose) {
+        Ok(true) => check(true, fill(Id::CheckRemoteExists, &[&remote])),
+        _ => check(false, fill(Id::CheckRemoteMissing, &[&remote])),
+    }
+
+    let merging = rev_parse(git, "MERGE_HEAD", verbose).is_ok();
+    let conflicted = get_current_branch(git, verbose)
+        .ok()
+        .and_then(|branch| get_config(git, &format!("cresca.{}.conflicted", branch), verbose))
+        .is_some();
+    if conflicted {
+        check(false, msg(Id::CheckConflicted).to_string());
+    } else if merging {
+        check(false, msg(Id::CheckMerging).to_string());
+    } else {
+        check(true, msg(Id::CheckNotMerging).to_string());
+    }
+}
+
+/// Sort the remaining files of the review status
+///
+/// # Arguments
+///
+/// * `status` - The review status whose `files` and `file_stats` are sorted.
+/// * `order` - The order to sort the files in.
+fn sort_files(status: &mut ReviewStatus, order: SortOrder) {
+    match order {
+        SortOrder::Name => {
+            status.files.sort();
+            status.file_stats.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        SortOrder::Size => {
+            status.file_stats.sort_by(|a, b| {
+                (b.insertions + b.deletions)
+                    .cmp(&(a.insertions + a.deletions))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            status.files = status
+                .file_stats
+                .iter()
+                .map(|stat| stat.path.clone())
+                .collect();
+        }
+    }
+}
+
+/// Run `render` every `interval` seconds, clearing the screen before each run, until interrupted with Ctrl-C
+///
+/// # Arguments
+///
+/// * `interval` - The number of seconds between runs.
+/// * `render` - The function printing the output.
+fn watch(interval: u64, render: impl Fn() -> Result<(), CrescaError>) -> Result<(), CrescaError> {
+    // Show the cursor again and exit successfully on Ctrl-C
+    ctrlc::set_handler(|| {
+        print!("\x1B[?25h");
+        let _ = io::stdout().flush();
+        exit(0);
+    })
+    .map_err(|e| CrescaError::SignalHandler(e.to_string()))?;
+
+    print!("\x1B[?25l");
+    loop {
+        print!("\x1B[2J\x1B[H");
+        if let Err(e) = render() {
+            print!("\x1B[?25h");
+            return Err(e);
+        }
+        let _ = io::stdout().flush();
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// Get what to switch back to if preparing the review branch is interrupted
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `worktree` - Whether the review is prepared in a worktree, so the current checkout is not switched back.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// The branch, or the commit with `true` if HEAD is detached. `None` when reviewing in a worktree.
+fn original_checkout(
+    git: &impl GitRunner,
+    worktree: bool,
+    verbose: u8,
+) -> Result<Option<(String, bool)>, CrescaError> {
+    Ok(if worktree {
+        None
+    } else if is_detached_head(git, verbose)? {
+        Some((rev_parse(git, "HEAD", verbose)?, true))
+    } else {
+        Some((get_current_branch(git, verbose)?, false))
+    })
+}
+
+/// Restore the repository after preparing the review branch was interrupted
+///
+/// The merge in progress is aborted, and the original checkout is switched back to. Commits already made
+/// on the review branch, such as the auto-approve commit, are kept.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `review_branch` - The name of the review branch being prepared.
+/// * `original` - The branch or detached commit to switch back to (see `original_checkout`).
+/// * `verbose` - The verbosity level (see `run_git_command`).
+///
+/// # Returns
+///
+/// `CrescaError::ReviewInterrupted` with the branch checked out now.
+fn restore_interrupted_review(
+    git: &impl GitRunner,
+    review_branch: &str,
+    original: Option<(String, bool)>,
+    verbose: u8,
+) -> CrescaError {
+    // git was interrupted along with cresca, so only the state it left behind is cleaned up
+    let _ = run_git_command(git, "abort merge", &["merge", "--abort"], verbose);
+    let current_branch = get_current_branch(git, verbose).unwrap_or_default();
+    if current_branch == review_branch {
+        // Squash merges and cherry-picks without committing leave no merge to abort
+        let _ = run_git_command(
+            git,
+            "discard merged changes",
+            &["reset", "--merge"],
+            verbose,
+        );
+    }
+    let restored = match original {
+        Some((branch, detached)) => {
+            let mut args = vec!["switch"];
+            if detached {
+                args.push("--detach");
+            }
+            args.push(&branch);
+            match run_git_command(git, &format!("switch back to {}", branch), &args, verbose) {
+                Ok(_) => branch,
+                Err(_) => current_branch,
+            }
+        }
+        None => current_branch,
+    };
+    CrescaError::ReviewInterrupted(restored)
+}
+
+/// Print the commits of a review with how they are handled, and the number of commits for each
+fn print_preview(to_branch: &str, from_branch: &str, commits: &[(String, CommitAction)]) {
+    println!(
+        "{}",
+        fill(
+            Id::CommitsToReview,
+            &[&to_branch.green(), &from_branch.green()]
+        )
+    );
+    if commits.is_empty() {
+        println!("  {}", msg(Id::NoCommits));
+    }
+    for (commit, action) in commits {
+        let label = match action {
+            CommitAction::AutoApprove => msg(Id::ActionAutoApprove).yellow(),
+            CommitAction::Review => msg(Id::ActionReview).green(),
+            CommitAction::Exclude => msg(Id::ActionExclude).red(),
+        };
+        println!("  {:<12} {}", label, commit);
+    }
+    let count = |action| commits.iter().filter(|(_, a)| *a == action).count();
+    println!(
+        "{}",
+        fill(
+            Id::PreviewSummary,
+            &[
+                &count(CommitAction::Review),
+                &count(CommitAction::AutoApprove),
+                &count(CommitAction::Exclude)
+            ]
+        )
+    );
+}
+
+/// Print the number of files staged for approval, with a hint for the next step
+fn print_staged_count(count: usize) {
+    println!(
+        "{}",
+        fill(Id::FilesStaged, &[&count, &"cresca approve".green()])
+    );
+}
+
+/// Print one-line commit logs indented, or `(none)` if there are no commits
+fn print_commits(commits: &[String]) {
+    if commits.is_empty() {
+        println!("  {}", msg(Id::NoCommits));
+    }
+    for commit in commits {
+        println!("  {}", commit);
+    }
+}
+
+/// Get the options of all commands that take a value, so the word following them is not a positional argument
+///
+/// # Arguments
+///
+/// * `command` - The command to collect the options of, including those of its subcommands.
+/// * `options` - The collected options, e.g. `--skip-to` and `-X`.
+fn collect_value_options(command: &clap::Command, options: &mut Vec<String>) {
+    for arg in command.get_arguments() {
+        if arg.is_positional() || !arg.get_action().takes_values() {
+            continue;
+        }
+        options.extend(arg.get_long().map(|long| format!("--{}", long)));
+        options.extend(arg.get_short().map(|short| format!("-{}", short)));
+    }
+    for subcommand in command.get_subcommands() {
+        collect_value_options(subcommand, options);
+    }
+}
+
+/// Get the completion candidates for the last word of a partial command line
+///
+/// Local branches are suggested for the branches of `cresca review` and `cresca switch`, and the commits of the review
+/// for `--skip-to` and `--stop-at`. Other words have no candidates, leaving them to the static completion.
+///
+/// # Arguments
+///
+/// * `git` - The runner of the git commands.
+/// * `words` - The words of the command line after `cresca`, ending with the word being completed.
+/// * `config` - The loaded configuration.
+/// * `verbose` - The verbosity level (see `run_git_command`).
+fn complete(git: &impl GitRunner, words: &[String], config: &Config, verbose: u8) -> Vec<String> {
+    let Some((current, previous)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let mut value_options = Vec::new();
+    collect_value_options(&Cli::command(), &mut value_options);
+
+    let mut positionals = Vec::new();
+    let mut range = None;
+    let mut iter = previous.iter();
+    while let Some(word) = iter.next() {
+        if value_options.contains(word) {
+            let value = iter.next();
+            if word == "--range" {
+                range = value;
+            }
+        } else if !word.starts_with('-') {
+            positionals.push(word.as_str());
+        }
+    }
+    if !matches!(positionals.first(), Some(&"review" | &"switch")) {
+        return Vec::new();
+    }
+
+    let candidates = match previous.last().map(String::as_str) {
+        Some("--skip-to" | "--stop-at") => {
+            let range = match (range, &positionals[1..], &config.default_to) {
+                (Some(range), _, _) => range.clone(),
+                (None, [to, from], _) => format!("{}..{}", to, from),
+                (None, [from], Some(default_to)) => format!("{}..{}", default_to, from),
+                _ => return Vec::new(),
+            };
+            rev_list(git, &range, verbose).unwrap_or_default()
+        }
+        Some(option)
+            if value_options
+                .iter()
+                .any(|value_option| value_option == option) =>
+        {
+            return Vec::new()
+        }
+        _ if current.starts_with('-') || positionals.len() > 2 => return Vec::new(),
+        _ => list_branches(git, verbose).unwrap_or_default(),
+    };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(current.as_str()))
+        .collect()
+}
+
+/// Generate the completion script for a shell
+///
+/// The bash and zsh scripts ask `cresca __complete` for branches and commits first, and fall back
+/// to the static completion generated by clap, which is renamed to `_cresca_static`.
+/// The fish script adds the candidates to the static completion.
+///
+/// # Arguments
+///
+/// * `shell` - The shell to generate the completion script for.
+fn completion_script(shell: Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "cresca", &mut script);
+    let script = String::from_utf8_lossy(&script);
+
+    let dynamic = match shell {
+        Shell::Bash => {
+            r#"_cresca() {
+    local candidates
+    candidates=$(cresca __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null)
+    if [[ -n "${candidates}" ]]; then
+        COMPREPLY=( ${candidates} )
+    else
+        _cresca_static "$@"
+    fi
+}
+"#
+        }
+        Shell::Zsh => {
+            r#"_cresca() {
+    local -a candidates
+    candidates=(${(f)"$(cresca __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+    if (( ${#candidates} )); then
+        compadd -a candidates
+    else
+        _cresca_static "$@"
+    fi
+}
+"#
+        }
+        Shell::Fish => {
+            return format!(
+                "{}complete -c cresca -f -a '(cresca __complete -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null)'\n",
+                script
+            );
+        }
+        _ => return script.into_owned(),
+    };
+    script.replacen(
+        "_cresca() {",
+        &format!("{}\n_cresca_static() {{", dynamic),
+        1,
+    )
+}
+
+/// Get the error for not being on a review branch
+fn not_on_review_branch(git: &impl GitRunner, verbose: u8) -> CrescaError {
+    match is_detached_head(git, verbose) {
+        Ok(true) => CrescaError::DetachedHead,
+        Ok(false) => CrescaError::NotReviewBranch,
+        Err(e) => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::truncate_path;
+    use unicode_width::UnicodeWidthStr;
+
+    /// Test that `truncate_path` keeps paths that fit and shortens the middle directories of others
+    #[test]
+    fn test_truncate_path_ascii() {
+        assert_eq!(truncate_path("src/main.rs", 20), "src/main.rs");
+        assert_eq!(truncate_path("src/main.rs", 11), "src/main.rs");
+        assert_eq!(
+            truncate_path("src/commands/review/prepare.rs", 20),
+            "src/…/prepare.rs"
+        );
+        assert_eq!(
+            truncate_path("src/commands/review/prepare.rs", 26),
+            "src/commands/…/prepare.rs"
+        );
+    }
+
+    /// Test that `truncate_path` keeps the end of a file name wider than the width
+    #[test]
+    fn test_truncate_path_too_narrow() {
+        assert_eq!(truncate_path("src/really_long_name.rs", 8), "…name.rs");
+        assert_eq!(truncate_path("src/main.rs", 1), "…");
+        assert_eq!(truncate_path("src/main.rs", 0), "…");
+    }
+
+    /// Test that `truncate_path` shortens a path without directories from its start
+    #[test]
+    fn test_truncate_path_single_component() {
+        assert_eq!(truncate_path("README.md", 9), "README.md");
+        assert_eq!(truncate_path("README.md", 6), "…ME.md");
+    }
+
+    /// Test that `truncate_path` counts the display width of non-ASCII paths rather than their bytes
+    #[test]
+    fn test_truncate_path_non_ascii() {
+        // Each of these characters is three bytes but two columns wide
+        let path = "ドキュメント/説明.md";
+        assert_eq!(path.width(), 20);
+        assert_eq!(truncate_path(path, 20), path);
+        assert_eq!(truncate_path(path, 19), "…/説明.md");
+        assert_eq!(truncate_path("説明書.md", 6), "…書.md");
+        // Accented letters are one column wide
+        assert_eq!(truncate_path("café/menü.txt", 13), "café/menü.txt");
     }
 }
@@ -0,0 +1,514 @@
+//! Catalog of the user-facing messages.
+//!
+//! Messages are looked up by their [`Id`] in the catalog of the language selected by the
+//! `CRESCA_LANG` environment variable, or by the locale (`LC_ALL`, `LC_MESSAGES` and `LANG`)
+//! if it is not set. Messages missing from a catalog fall back to English.
+//!
+//! Templates use `{}` for the next argument and `{0}`, `{1}`, ... for an argument by position,
+//! so a translation can reorder the arguments. `{{` and `}}` are literal braces.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Language of the user-facing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    /// English, which has every message.
+    En,
+    /// Japanese, which only has some of the messages so far.
+    Ja,
+}
+
+impl Lang {
+    /// Parse a language from a locale name such as `ja`, `ja_JP.UTF-8` or `en-US`
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale name.
+    ///
+    /// # Returns
+    ///
+    /// The language, or `None` if there is no catalog for it.
+    pub fn from_locale(locale: &str) -> Option<Lang> {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "ja" => Some(Lang::Ja),
+            _ => None,
+        }
+    }
+}
+
+/// Identifier of a user-facing message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Id {
+    // Labels
+    Error,
+    Warning,
+    Approved,
+    Remaining,
+    Binary,
+    NoCommits,
+    ActionAutoApprove,
+    ActionReview,
+    ActionExclude,
+    ColumnBranch,
+    ColumnFrom,
+    ColumnFiles,
+    ColumnReviewed,
+
+    // Command output
+    OriginalGitError,
+    ReviewAborted,
+    SkippingFile,
+    ApprovalCancelled,
+    ChangesApproved,
+    NothingToApprove,
+    ReviewingCommit,
+    BranchDeleted,
+    BranchSkipped,
+    BranchesDeleted,
+    NothingToExport,
+    WriteFailed,
+    ChangesExported,
+    ChangesMerged,
+    NoReviewBranches,
+    ReviewCancelled,
+    ChangesStashed,
+    AlreadyMerged,
+    DryRunFinished,
+    ReviewInWorktree,
+    FromBranchGone,
+    ShortFromBranchGone,
+    ShortDetachedHead,
+    ShortNotOnReviewBranch,
+    SwitchedToReviewBranch,
+    ApprovalUndone,
+    MissingFromBranch,
+    InvalidRange,
+    NotACommit,
+    ReviewPrepared,
+    ReviewPreparedEmpty,
+    ReviewStatus,
+    RemainingDiff,
+    FileCount,
+    FilesCount,
+    InsertionCount,
+    InsertionsCount,
+    DeletionCount,
+    DeletionsCount,
+    BinaryFiles,
+    Progress,
+    FilesRemaining,
+    MoreFile,
+    MoreFiles,
+    CommitsToReview,
+    PreviewSummary,
+    FilesStaged,
+    AutoApproveCommit,
+    AutoApproveCommits,
+    NotDescendant,
+    MergeBaseMoved,
+    CommitsToAutoApprove,
+    ConfirmDiscard,
+    ConfirmRecreate,
+    StageEachFile,
+    StatsSkipped,
+    Fetching,
+    FetchingPullRequest,
+    SquashMerging,
+
+    // Doctor checks
+    CheckGitVersionUnknown,
+    CheckNotInWorkTree,
+    CheckInWorkTree,
+    CheckHeadDetached,
+    CheckCurrentBranch,
+    CheckCurrentBranchUnknown,
+    CheckOnReviewBranch,
+    CheckNotOnReviewBranch,
+    CheckClean,
+    CheckDirty,
+    CheckRemoteExists,
+    CheckRemoteMissing,
+    CheckConflicted,
+    CheckMerging,
+    CheckNotMerging,
+
+    // Errors
+    SigningFailed,
+    GitNotFound,
+    NotGitRepository,
+    NotReviewBranch,
+    UnknownReviewBranch,
+    NoReviewBranch,
+    DetachedHead,
+    DirtyWorkingTree,
+    InvalidConfig,
+    UnknownConfigKey,
+    RemoteNotFound,
+    SameBranch,
+    SameCommit,
+    UnrelatedHistories,
+    CommitNotInRange,
+    AmbiguousCommit,
+    SkipToMergeCommit,
+    InvalidAuthor,
+    GitTimeout,
+    ReviewedCommitsDisappeared,
+    ReviewedCommitsDisappearedHint,
+    StopAtBeforeSkipTo,
+    MergeConflicts,
+    MergeConflictsHint,
+    NothingToContinue,
+    UnresolvedConflicts,
+    BranchNotFound,
+    RevisionNotACommit,
+    NothingToUndoAutoApproved,
+    NothingToUndo,
+    StagedChanges,
+    NotInLatestApproval,
+    NotInLatestApprovalHint,
+    FileUnapproved,
+    InvalidGlob,
+    InvalidFormat,
+    UnreviewedChanges,
+    Worktree,
+    Interrupted,
+    ReviewInterrupted,
+    SignalHandler,
+    LogFile,
+    GitHubCliNotFound,
+    GitHub,
+}
+
+/// Get the language of the user-facing messages
+///
+/// The language is read from the environment once and cached.
+///
+/// # Returns
+///
+/// The language selected by `CRESCA_LANG`, or by the locale if it is not set, falling back to English.
+pub fn lang() -> Lang {
+    static LANG: OnceLock<Lang> = OnceLock::new();
+    *LANG.get_or_init(|| {
+        ["CRESCA_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::from_locale(&value))
+            .unwrap_or(Lang::En)
+    })
+}
+
+/// Get the template of a message in the current language
+///
+/// # Arguments
+///
+/// * `id` - The identifier of the message.
+///
+/// # Returns
+///
+/// The template of the message, or the English one if it is not translated.
+pub fn msg(id: Id) -> &'static str {
+    match lang() {
+        Lang::En => english(id),
+        Lang::Ja => japanese(id).unwrap_or_else(|| english(id)),
+    }
+}
+
+/// Get a message in the current language with its placeholders filled in
+///
+/// # Arguments
+///
+/// * `id` - The identifier of the message.
+/// * `args` - The arguments to fill the placeholders with.
+///
+/// # Returns
+///
+/// The message with its placeholders replaced by the arguments.
+pub fn fill(id: Id, args: &[&dyn fmt::Display]) -> String {
+    let mut result = String::new();
+    let mut next = 0;
+    let mut chars = msg(id).chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index.parse().unwrap_or(usize::MAX)
+                };
+                if let Some(arg) = args.get(index) {
+                    result.push_str(&arg.to_string());
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Get a message in the current language in its singular or plural form
+///
+/// # Arguments
+///
+/// * `count` - The count selecting the form.
+/// * `one` - The identifier of the singular form, used when `count` is 1.
+/// * `other` - The identifier of the plural form.
+/// * `args` - The arguments to fill the placeholders with.
+///
+/// # Returns
+///
+/// The message in the form for `count` with its placeholders replaced by the arguments.
+pub fn fill_plural(count: usize, one: Id, other: Id, args: &[&dyn fmt::Display]) -> String {
+    fill(if count == 1 { one } else { other }, args)
+}
+
+/// English catalog, which has every message
+fn english(id: Id) -> &'static str {
+    match id {
+        Id::Error => "error",
+        Id::Warning => "warning",
+        Id::Approved => "Approved",
+        Id::Remaining => "Remaining",
+        Id::Binary => "binary",
+        Id::NoCommits => "(none)",
+        Id::ActionAutoApprove => "auto-approve",
+        Id::ActionReview => "review",
+        Id::ActionExclude => "exclude",
+        Id::ColumnBranch => "BRANCH",
+        Id::ColumnFrom => "FROM",
+        Id::ColumnFiles => "FILES",
+        Id::ColumnReviewed => "REVIEWED",
+
+        Id::OriginalGitError => "Original error from git:",
+        Id::ReviewAborted => "Review aborted. Switched back to {} branch.",
+        Id::SkippingFile => "Skipping {}, which has no remaining changes.",
+        Id::ApprovalCancelled => "Approval cancelled.",
+        Id::ChangesApproved => "Reviewed changes were approved successfully.",
+        Id::NothingToApprove => "There are no reviewed changes to approve. Ending the review.",
+        Id::ReviewingCommit => "Reviewing commit {}.",
+        Id::BranchDeleted => "Deleted {}",
+        Id::BranchSkipped => {
+            "Skipped {}: unapproved changes remain; use `{}` to delete it anyway."
+        }
+        Id::BranchesDeleted => "{} review branch(es) deleted.",
+        Id::NothingToExport => "There are no approved changes to export.",
+        Id::WriteFailed => "Failed to write {}: {}",
+        Id::ChangesExported => "Approved changes were exported to {}.",
+        Id::ChangesMerged => "Approved changes were merged into {}.",
+        Id::NoReviewBranches => "No review branches found.",
+        Id::ReviewCancelled => "Review cancelled.",
+        Id::ChangesStashed => {
+            "Uncommitted changes were stashed. Run `{}` on {} after the review to restore them."
+        }
+        Id::AlreadyMerged => "{} is already merged into {}; there is nothing to review.",
+        Id::DryRunFinished => "Dry run finished. No changes were made.",
+        Id::ReviewInWorktree => {
+            "The review branch is in the worktree {}. Run `{}` to start reviewing."
+        }
+        Id::FromBranchGone => {
+            "Branch {} no longer exists. Run `{}` to end the review, or `{}` to review it again with the right branches."
+        }
+        Id::ShortFromBranchGone => "{} no longer exists",
+        Id::ShortDetachedHead => "detached HEAD",
+        Id::ShortNotOnReviewBranch => "not on a review branch",
+        Id::SwitchedToReviewBranch => "Switched to review branch {}.",
+        Id::ApprovalUndone => "Undid approval {}. Its changes are unstaged for review again.",
+        Id::FileUnapproved => "Unapproved {}. Its changes are unstaged for review again.",
+        Id::MissingFromBranch => {
+            "The development branch is missing. Specify both branches or set `{}` in the config file."
+        }
+        Id::InvalidRange => "Invalid range `{}`; expected `<base>..<head>`.",
+        Id::NotACommit => "{} in the range is not a commit.",
+        Id::ReviewPrepared => {
+            "Review branch prepared successfully. Stage the changes you have reviewed and run `{}` to approve them."
+        }
+        Id::ReviewPreparedEmpty => {
+            "Review branch prepared successfully. However, it seems like there are no unreviewed changes."
+        }
+        Id::ReviewStatus => "Review status:",
+        Id::RemainingDiff => "Remaining diff to {}: {}{}, {}, {}",
+        Id::FileCount => "{} file",
+        Id::FilesCount => "{} files",
+        Id::InsertionCount => "{} insertion",
+        Id::InsertionsCount => "{} insertions",
+        Id::DeletionCount => "{} deletion",
+        Id::DeletionsCount => "{} deletions",
+        Id::BinaryFiles => " ({} binary)",
+        Id::Progress => "Progress: {} reviewed ({} of {} lines)",
+        Id::FilesRemaining => "Files remaining:",
+        Id::MoreFile => "... and {} more file",
+        Id::MoreFiles => "... and {} more files",
+        Id::CommitsToReview => "Commits to review ({} <- {}):",
+        Id::PreviewSummary => "{} to review, {} auto-approved, {} excluded.",
+        Id::AutoApproveCommit => "Auto-approve {} commit up to {}",
+        Id::AutoApproveCommits => "Auto-approve {} commits up to {}",
+        Id::FilesStaged => "{} file(s) staged for approval. Run `{}` to approve them.",
+        Id::NotDescendant => {
+            "{} is not a descendant of {}; only the changes since their merge base are reviewed."
+        }
+        Id::MergeBaseMoved => {
+            "The merge base of {0} and {1} has moved since the review branch was created ({2} -> {3}), because {0} gained commits that {1} now includes. The squash merge may bring in changes of {0}; run `{4}` to recreate the review branch from the new merge base."
+        }
+        Id::CommitsToAutoApprove => "Commits to be auto-approved:",
+        Id::ConfirmDiscard => "This will discard {} unreviewed file(s). Continue? [y/N] ",
+        Id::ConfirmRecreate => "This will recreate {} and discard its approvals. Continue? [y/N] ",
+        Id::StageEachFile => {
+            "Stage each file? ({} = stage, {} = skip, {} = skip all remaining files)"
+        }
+        Id::StatsSkipped => "Skipping {}: {}",
+        Id::Fetching => "Fetching {}…",
+        Id::FetchingPullRequest => "Fetching pull request #{}…",
+        Id::SquashMerging => "Squash-merging {}…",
+
+        Id::CheckGitVersionUnknown => "git version unknown: {}",
+        Id::CheckNotInWorkTree => "Not inside a git work tree",
+        Id::CheckInWorkTree => "Inside a git work tree",
+        Id::CheckHeadDetached => "HEAD is detached",
+        Id::CheckCurrentBranch => "Current branch: {}",
+        Id::CheckCurrentBranchUnknown => "Current branch unknown: {}",
+        Id::CheckOnReviewBranch => "On a review branch of {} (target: {})",
+        Id::CheckNotOnReviewBranch => "Not on a review branch",
+        Id::CheckClean => "Working tree is clean",
+        Id::CheckDirty => "Working tree has uncommitted changes",
+        Id::CheckRemoteExists => "Remote {} exists",
+        Id::CheckRemoteMissing => "Remote {} does not exist",
+        Id::CheckConflicted => {
+            "A squash merge stopped on conflicts; run `cresca continue` after resolving them"
+        }
+        Id::CheckMerging => "A merge is in progress",
+        Id::CheckNotMerging => "No merge in progress",
+
+        Id::SigningFailed => {
+            "{} Signing the commit failed; check the signing setup of git (e.g. `user.signingkey` and `gpg.format`), or set `{}` to disable signing."
+        }
+        Id::GitNotFound => "git executable {} not found; install git or set {} to its path.",
+        Id::NotGitRepository => {
+            "Not a git repository. Run cresca inside the repository you want to review."
+        }
+        Id::NotReviewBranch => "Not on a review branch; run `{}` to prepare a review branch.",
+        Id::UnknownReviewBranch => {
+            "{} is not a review branch; run `{}` to see the review branches."
+        }
+        Id::NoReviewBranch => "There is no review branch for {} and {}; run `{}` to prepare one.",
+        Id::DetachedHead => "You are in a detached HEAD state; checkout a branch first.",
+        Id::DirtyWorkingTree => {
+            "Uncommitted changes found. Please commit or stash them first, or use `{}` to start a review anyway."
+        }
+        Id::InvalidConfig => "Failed to parse config file {}.\n{}",
+        Id::UnknownConfigKey => "Unknown config key `{}`. The known keys are:",
+        Id::RemoteNotFound => "Remote {} does not exist. Use `{}` to select another remote.",
+        Id::SameBranch => "The target branch and the development branch are the same ({}).",
+        Id::SameCommit => "{} and {} point to the same commit; there is nothing to review.",
+        Id::UnrelatedHistories => {
+            "{0} and {1} have unrelated histories, so there is no merge base to review from. Use `{2}` to review all changes of {1} from an empty tree."
+        }
+        Id::CommitNotInRange => "Commit {} is not in the range {}",
+        Id::AmbiguousCommit => "Ambiguous commit `{}`: matches {} commits",
+        Id::SkipToMergeCommit => {
+            "Commit {0} is a merge commit. Skipping to it would auto-approve only the history of its first parent and leave the changes it merges half reviewed. Use `{1}` with the merge commit's child or with a commit before the merge instead."
+        }
+        Id::InvalidAuthor => {
+            "Invalid author `{}`; use the `Name <email>` format, e.g. `Jane Doe <jane@example.com>`."
+        }
+        Id::GitTimeout => {
+            "Timed out trying to {} after {} second(s). Check that the remote is reachable, or raise `{}`."
+        }
+        Id::ReviewedCommitsDisappeared => {
+            "{} was rewritten since the last review, e.g. by a rebase and force-push. These reviewed commits are no longer in it:"
+        }
+        Id::ReviewedCommitsDisappearedHint => {
+            "Their approvals cannot be carried over. Run `{}` to recreate the review branch and review the new history."
+        }
+        Id::StopAtBeforeSkipTo => "--stop-at ({}) must be at or after --skip-to ({})",
+        Id::MergeConflicts => "Merge conflicts found while trying to {}:",
+        Id::MergeConflictsHint => {
+            "The conflicted changes are left in the working tree. Resolve and stage them, then run `{}` to continue reviewing, or run `{}` to start over."
+        }
+        Id::NothingToContinue => {
+            "Nothing to continue. No squash merge stopped on conflicts on this branch."
+        }
+        Id::UnresolvedConflicts => "Unresolved conflicts remain. Resolve and stage them first:",
+        Id::RevisionNotACommit => "`{}` does not resolve to a commit.",
+        Id::BranchNotFound => {
+            "Branch {} no longer exists and the default branch could not be determined."
+        }
+        Id::NothingToUndoAutoApproved => {
+            "Nothing to undo. The auto-approve commit created by `{}` cannot be undone."
+        }
+        Id::NothingToUndo => {
+            "Nothing to undo. No changes have been approved on this review branch."
+        }
+        Id::StagedChanges => {
+            "There are staged changes. Approve them with `{}` or unstage them with `{}` first."
+        }
+        Id::NotInLatestApproval => {
+            "These paths were not changed by the most recent approve commit, so they cannot be unapproved:"
+        }
+        Id::NotInLatestApprovalHint => {
+            "Only files of the most recent approval can be unapproved; the auto-approve commit cannot be unapproved. Run `{}` repeatedly to undo earlier approvals as a whole."
+        }
+        Id::InvalidGlob => "Invalid glob `{}`: {}",
+        Id::InvalidFormat => {
+            "Invalid format: {}. Available placeholders are {{from_branch}}, {{file_count}}, {{insertions}}, {{deletions}}, {{binary_files}}, {{total_insertions}}, {{total_deletions}} and {{reviewed_percent}}."
+        }
+        Id::UnreviewedChanges => {
+            "{} file(s) still have unreviewed changes. Approve them first, or use `{}` to finalize only the approved changes."
+        }
+        Id::Worktree => "Failed to enter the review worktree {}.\n{}",
+        Id::Interrupted => "Interrupted.",
+        Id::ReviewInterrupted => "Interrupted. The merge in progress was aborted, and {} is checked out. Commits already made on the review branch are kept.",
+        Id::SignalHandler => "Failed to install the Ctrl-C handler: {}",
+        Id::LogFile => "Failed to open the log file {}.\n{}",
+        Id::GitHubCliNotFound => {
+            "GitHub CLI (gh) is not installed. Install it from https://cli.github.com or specify the branches explicitly."
+        }
+        Id::GitHub => "Failed to {}.\n\t{}",
+    }
+}
+
+/// Japanese catalog, which only has some of the messages so far
+fn japanese(id: Id) -> Option<&'static str> {
+    Some(match id {
+        Id::Error => "エラー",
+        Id::Warning => "警告",
+        Id::ReviewAborted => "レビューを中止しました。{} ブランチに戻りました。",
+        Id::ApprovalCancelled => "承認を取り消しました。",
+        Id::ChangesApproved => "レビューした変更を承認しました。",
+        Id::NoReviewBranches => "レビューブランチが見つかりません。",
+        Id::ReviewCancelled => "レビューを取り消しました。",
+        Id::ReviewStatus => "レビューの状況:",
+        Id::NotReviewBranch => {
+            "レビューブランチ上にいません。`{}` を実行してレビューブランチを準備してください。"
+        }
+        Id::DirtyWorkingTree => {
+            "コミットされていない変更があります。先にコミットまたはスタッシュするか、`{}` を使ってレビューを開始してください。"
+        }
+        Id::MergeBaseMoved => {
+            "レビューブランチの作成後に {0} と {1} のマージベースが移動しました ({2} -> {3})。{0} に追加されたコミットを {1} が取り込んだためです。スカッシュマージに {0} の変更が含まれる可能性があります。`{4}` を実行すると新しいマージベースからレビューブランチを作り直します。"
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,69 @@
+//! Spinner shown on stderr while long-running git commands run.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Frames of the spinner, drawn in turn
+const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long each frame is shown
+const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A spinner followed by a message, redrawn on stderr until it is dropped
+///
+/// The line is cleared when the spinner is dropped, so an error returned with `?` while the spinner
+/// is shown is printed on a clean line.
+pub(crate) struct Spinner {
+    /// Stops the drawing thread when dropped.
+    stop: Option<Sender<()>>,
+    /// The drawing thread.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Start drawing a spinner with a message
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message shown next to the spinner, e.g. `Fetching develop…`.
+    /// * `enabled` - Whether to draw the spinner. Nothing is drawn if `false`.
+    pub(crate) fn start(message: String, enabled: bool) -> Self {
+        if !enabled {
+            return Spinner {
+                stop: None,
+                handle: None,
+            };
+        }
+
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            let mut stderr = io::stderr();
+            for frame in FRAMES.iter().cycle() {
+                let _ = write!(stderr, "\r{} {}", frame, message);
+                let _ = stderr.flush();
+                match stopped.recv_timeout(FRAME_INTERVAL) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+            let _ = write!(stderr, "\r\x1B[2K");
+            let _ = stderr.flush();
+        });
+        Spinner {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    /// Stop the drawing thread and clear the line
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -1,13 +1,22 @@
+#[cfg(not(feature = "libgit2"))]
+use cresca::git::{GitError, GitRunner};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+#[cfg(not(feature = "libgit2"))]
+use std::process::ExitStatus;
+use std::process::{Child, Command, Output, Stdio};
+#[cfg(not(feature = "libgit2"))]
+use std::sync::Mutex;
 use tempfile::TempDir;
 
 /// A temporary git repository for testing.
 /// The repository is automatically cleaned up when this struct is dropped.
-/// Includes a bare "remote" repository to simulate `git pull origin`.
+/// Includes a bare "remote" repository to simulate `git pull origin`, and an isolated
+/// `XDG_CONFIG_HOME` so that the global cresca config of the host is not used.
 pub struct TempGitRepo {
     pub dir: TempDir,
     pub remote_dir: TempDir,
+    pub config_dir: TempDir,
 }
 
 impl TempGitRepo {
@@ -15,6 +24,7 @@ impl TempGitRepo {
     pub fn new() -> Self {
         let remote_dir = TempDir::new().expect("Failed to create remote temp directory");
         let dir = TempDir::new().expect("Failed to create temp directory");
+        let config_dir = TempDir::new().expect("Failed to create config temp directory");
 
         // Initialize bare remote repository
         Command::new("git")
@@ -23,7 +33,11 @@ impl TempGitRepo {
             .output()
             .expect("Failed to initialize bare repo");
 
-        let repo = Self { dir, remote_dir };
+        let repo = Self {
+            dir,
+            remote_dir,
+            config_dir,
+        };
 
         // Initialize working git repo
         repo.git(&["init", "-b", "main"]);
@@ -80,6 +94,57 @@ impl TempGitRepo {
         std::fs::write(&path, content).expect("Failed to write file");
     }
 
+    /// Writes the global cresca config file.
+    pub fn write_global_config(&self, content: &str) {
+        let path = self.config_dir.path().join("cresca").join("config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create config directory");
+        std::fs::write(&path, content).expect("Failed to write config file");
+    }
+
+    /// Returns the `bin` directory outside the repository for fake commands.
+    #[cfg(unix)]
+    fn bin_dir(&self) -> PathBuf {
+        let bin_dir = self.config_dir.path().join("bin");
+        std::fs::create_dir_all(&bin_dir).expect("Failed to create bin directory");
+        bin_dir
+    }
+
+    /// Writes an executable script to the `bin` directory and returns a `PATH` value where the
+    /// script takes precedence over the commands of the host.
+    #[cfg(unix)]
+    pub fn write_fake_command(&self, name: &str, script: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = self.bin_dir().join(name);
+        std::fs::write(&path, script).expect("Failed to write script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .expect("Failed to make script executable");
+        format!(
+            "{}:{}",
+            self.bin_dir().display(),
+            std::env::var("PATH").expect("PATH is not set")
+        )
+    }
+
+    /// Returns the path to the git executable on `PATH`.
+    pub fn git_executable() -> PathBuf {
+        let system_path = std::env::var("PATH").expect("PATH is not set");
+        std::env::split_paths(&system_path)
+            .map(|dir| dir.join("git"))
+            .find(|path| path.is_file())
+            .expect("git is not found in PATH")
+    }
+
+    /// Returns a `PATH` value where only git is available.
+    #[cfg(all(unix, feature = "github"))]
+    pub fn git_only_path(&self) -> String {
+        let link = self.bin_dir().join("git");
+        if !link.exists() {
+            std::os::unix::fs::symlink(Self::git_executable(), link).expect("Failed to link git");
+        }
+        self.bin_dir().display().to_string()
+    }
+
     /// Creates a commit with the given message.
     pub fn commit(&self, message: &str) {
         self.git(&["commit", "-m", message]);
@@ -110,14 +175,159 @@ impl TempGitRepo {
     pub fn run_cresca(&self, args: &[&str]) -> Output {
         Command::new(Self::cresca_binary())
             .args(args)
+            .env("XDG_CONFIG_HOME", self.config_dir.path())
+            .env("CRESCA_LANG", "en")
+            .current_dir(self.path())
+            .output()
+            .expect("Failed to execute cresca")
+    }
+
+    /// Runs cresca with the given arguments in another directory, such as a worktree of the repository.
+    pub fn run_cresca_in(&self, dir: &Path, args: &[&str]) -> Output {
+        Command::new(Self::cresca_binary())
+            .args(args)
+            .env("XDG_CONFIG_HOME", self.config_dir.path())
+            .env("CRESCA_LANG", "en")
+            .current_dir(dir)
+            .output()
+            .expect("Failed to execute cresca")
+    }
+
+    /// Runs cresca with the given arguments and environment variables.
+    pub fn run_cresca_with_env(&self, args: &[&str], envs: &[(&str, &str)]) -> Output {
+        Command::new(Self::cresca_binary())
+            .args(args)
+            .env("XDG_CONFIG_HOME", self.config_dir.path())
+            .env("CRESCA_LANG", "en")
+            .envs(envs.iter().copied())
             .current_dir(self.path())
             .output()
             .expect("Failed to execute cresca")
     }
 
+    /// Starts cresca with the given arguments and environment variables without waiting for it.
+    /// cresca leads its own process group, which can be signaled like a terminal does with `kill -- -<pid>`.
+    #[cfg(unix)]
+    pub fn spawn_cresca_with_env(&self, args: &[&str], envs: &[(&str, &str)]) -> Child {
+        use std::os::unix::process::CommandExt;
+
+        Command::new(Self::cresca_binary())
+            .process_group(0)
+            .args(args)
+            .env("XDG_CONFIG_HOME", self.config_dir.path())
+            .env("CRESCA_LANG", "en")
+            .envs(envs.iter().copied())
+            .current_dir(self.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute cresca")
+    }
+
+    /// Runs cresca with the given arguments, writing `input` to its stdin.
+    pub fn run_cresca_with_input(&self, args: &[&str], input: &str) -> Output {
+        let mut child = Command::new(Self::cresca_binary())
+            .args(args)
+            .env("XDG_CONFIG_HOME", self.config_dir.path())
+            .env("CRESCA_LANG", "en")
+            .current_dir(self.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute cresca");
+        child
+            .stdin
+            .take()
+            .expect("Failed to open stdin")
+            .write_all(input.as_bytes())
+            .expect("Failed to write to stdin");
+        child.wait_with_output().expect("Failed to wait for cresca")
+    }
+
     /// Checks if there are uncommitted changes.
     pub fn has_uncommitted_changes(&self) -> bool {
         let output = self.git(&["status", "--porcelain"]);
         !output.stdout.is_empty()
     }
 }
+
+/// A git runner answering the git commands from a table instead of running git.
+/// Records the commands so that a test can check which git commands were run.
+/// Only available without libgit2, which answers some queries without running git.
+#[cfg(not(feature = "libgit2"))]
+pub struct MockGitRunner {
+    /// Pairs of the leading arguments of a command and its stdout, where `None` makes it fail.
+    /// The first matching pair answers the command, and unmatched commands fail.
+    responses: Vec<(Vec<String>, Option<String>)>,
+    /// Leading arguments of commands and how many more times they fail before they are answered.
+    failures: Mutex<Vec<(Vec<String>, usize)>>,
+    pub calls: Mutex<Vec<String>>,
+}
+
+#[cfg(not(feature = "libgit2"))]
+impl MockGitRunner {
+    /// Creates a runner answering the commands starting with the given arguments.
+    pub fn new(responses: &[(&[&str], Option<&str>)]) -> Self {
+        MockGitRunner {
+            responses: responses
+                .iter()
+                .map(|(args, stdout)| {
+                    (
+                        args.iter().map(|arg| arg.to_string()).collect(),
+                        stdout.map(str::to_string),
+                    )
+                })
+                .collect(),
+            failures: Mutex::new(Vec::new()),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Makes the first `times` commands starting with the given arguments fail.
+    pub fn failing_first(self, args: &[&str], times: usize) -> Self {
+        self.failures
+            .lock()
+            .unwrap()
+            .push((args.iter().map(|arg| arg.to_string()).collect(), times));
+        self
+    }
+}
+
+#[cfg(not(feature = "libgit2"))]
+impl GitRunner for MockGitRunner {
+    fn run(&self, description: &str, args: &[&str]) -> Result<Output, GitError> {
+        self.calls.lock().unwrap().push(args.join(" "));
+        let matches = |prefix: &Vec<String>| {
+            args.starts_with(&prefix.iter().map(String::as_str).collect::<Vec<_>>())
+        };
+        let failing = self
+            .failures
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|(prefix, times)| *times > 0 && matches(prefix))
+            .map(|(_, times)| *times -= 1)
+            .is_some();
+        let stdout = self
+            .responses
+            .iter()
+            .find(|(prefix, _)| matches(prefix))
+            .and_then(|(_, stdout)| stdout.clone())
+            .filter(|_| !failing);
+        match stdout {
+            Some(stdout) => Ok(Output {
+                status: ExitStatus::default(),
+                stdout: stdout.into_bytes(),
+                stderr: Vec::new(),
+            }),
+            None => Err(GitError {
+                description: description.to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                stderr: String::new(),
+                not_found: false,
+                code: Some(1),
+            }),
+        }
+    }
+}
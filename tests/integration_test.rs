@@ -270,6 +270,236 @@ fn test_review_with_skip_to_option() {
     );
 }
 
+/// Test that `cresca review --skip-to` the oldest commit of the review auto-approves nothing.
+#[test]
+fn test_review_with_skip_to_oldest_commit() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.git(&["add", "."]);
+    repo.commit("Add file1");
+
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add file2");
+
+    let log_output = repo.git(&["rev-list", "--reverse", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let file1_hash = log_str.lines().next().unwrap().to_string();
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--no-pull",
+        "--skip-to",
+        &file1_hash,
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to the oldest commit should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let approved = repo.git(&["rev-list", "main..HEAD"]);
+    assert!(
+        approved.stdout.is_empty(),
+        "No auto-approve commit should be created"
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("file1.txt") && status_str.contains("file2.txt"),
+        "All changes should be left to review, got: {}",
+        status_str
+    );
+
+    // The oldest commit of an unrelated history is a root commit without a parent
+    repo.git(&["checkout", "--quiet", "--orphan", "other"]);
+    repo.git(&["rm", "-rf", "--quiet", "."]);
+    repo.write_file("other.txt", "unrelated content");
+    repo.git(&["add", "."]);
+    repo.commit("Start unrelated history");
+    let root_hash = String::from_utf8_lossy(&repo.git(&["rev-parse", "HEAD"]).stdout)
+        .trim()
+        .to_string();
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "other",
+        "--no-pull",
+        "--allow-unrelated",
+        "--skip-to",
+        &root_hash,
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to a root commit should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    assert!(
+        String::from_utf8_lossy(&status.stdout).contains("other.txt"),
+        "The root commit should be left to review"
+    );
+}
+
+/// Test that `cresca review --path` limits the unreviewed changes and the status to the paths.
+#[test]
+fn test_review_with_paths() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    std::fs::create_dir_all(repo.path().join("src")).unwrap();
+    std::fs::create_dir_all(repo.path().join("docs")).unwrap();
+    repo.write_file("src/lib.rs", "fn main() {}");
+    repo.write_file("docs/guide.md", "# Guide");
+    repo.write_file("README.md", "# Updated");
+    repo.git(&["add", "."]);
+    repo.commit("Add sources and docs");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--path", "src"]);
+    assert!(
+        output.status.success(),
+        "cresca review --path should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let status = repo.git(&["status", "--porcelain", "--untracked-files=all"]);
+    assert_eq!(
+        String::from_utf8_lossy(&status.stdout),
+        "?? src/lib.rs\n",
+        "Only the changes in src should be left to review"
+    );
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["files"], serde_json::json!(["src/lib.rs"]));
+    assert_eq!(json["total_insertions"], 1);
+
+    // Approving the scoped changes completes the scoped review
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["file_count"], 0);
+    assert_eq!(json["reviewed_percent"], 100);
+}
+
+/// Test that `cresca review --auto-approve` commits the matching files right after the squash merge.
+#[test]
+fn test_review_auto_approve_glob() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("Cargo.lock", "# lockfile");
+    repo.write_file("schema.generated.rs", "// generated");
+    repo.write_file("feature.rs", "fn feature() {}");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--auto-approve", "[a"]);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Invalid glob `[a`"),
+        "Should show error about the invalid glob, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    repo.git(&["config", "cresca.autoApprove", "*.generated.rs"]);
+    let output = repo.run_cresca(&["review", "main", "develop", "--auto-approve", "Cargo.lock"]);
+    assert!(
+        output.status.success(),
+        "cresca review --auto-approve should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("Cargo.lock") && files_str.contains("schema.generated.rs"),
+        "Matching files should be auto-approved, got: {}",
+        files_str
+    );
+    assert!(
+        !files_str.contains("feature.rs"),
+        "Other files should not be auto-approved"
+    );
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["files"], serde_json::json!(["feature.rs"]));
+}
+
+/// Test that `cresca review --exclude` leaves the matching files out of the review without approving them.
+#[test]
+fn test_review_exclude_glob() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    std::fs::create_dir_all(repo.path().join("vendor")).unwrap();
+    repo.write_file("vendor/lib.js", "// vendored");
+    repo.write_file("feature.rs", "fn feature() {}");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--exclude", "vendor/**"]);
+    assert!(
+        output.status.success(),
+        "cresca review --exclude should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let status = repo.git(&["status", "--porcelain", "--untracked-files=all"]);
+    assert_eq!(
+        String::from_utf8_lossy(&status.stdout),
+        "?? feature.rs\n",
+        "Excluded files should be reverted"
+    );
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["files"], serde_json::json!(["feature.rs"]));
+    let output = repo.run_cresca(&["diff", "--stat"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("feature.rs") && !stdout.contains("vendor"),
+        "cresca diff should leave the excluded files out, got: {}",
+        stdout
+    );
+
+    // Untracked files in excluded paths survive the approval
+    repo.write_file("vendor/local.js", "// local");
+    repo.git(&["add", "feature.rs"]);
+    repo.run_cresca(&["approve"]);
+    assert!(
+        repo.path().join("vendor/local.js").exists(),
+        "Untracked excluded files should be kept"
+    );
+    let files_in_head = repo.git(&["ls-tree", "-r", "--name-only", "HEAD"]);
+    assert!(
+        !String::from_utf8_lossy(&files_in_head.stdout).contains("vendor"),
+        "Excluded files should not be approved"
+    );
+}
+
 /// Test that `cresca review --skip-to` with already approved commits works correctly.
 #[test]
 fn test_review_with_skip_to_already_approved() {
@@ -347,7 +577,7 @@ fn test_status_shows_diff_stats() {
         stdout.contains("Remaining diff to develop"),
         "Should mention develop branch"
     );
-    assert!(stdout.contains("2 file(s)"), "Should show 2 files changed");
+    assert!(stdout.contains("2 files"), "Should show 2 files changed");
     assert!(stdout.contains("feature1.txt"), "Should list feature1.txt");
     assert!(stdout.contains("feature2.txt"), "Should list feature2.txt");
 }
@@ -394,7 +624,7 @@ fn test_status_after_partial_approval() {
     let output = repo.run_cresca(&["status"]);
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
-        stdout.contains("3 file(s)"),
+        stdout.contains("3 files"),
         "Should initially show 3 files, got: {}",
         stdout
     );
@@ -421,6 +651,17 @@ fn test_status_after_partial_approval() {
         "file3.txt should be in remaining files, got: {}",
         stdout
     );
+
+    // A single remaining file and line are counted in the singular
+    repo.git(&["add", "file2.txt"]);
+    repo.run_cresca(&["approve"]);
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("1 file, +1 insertion, -0 deletions"),
+        "Should use the singular for a count of 1, got: {}",
+        stdout
+    );
 }
 
 /// Test that `cresca review --stop-at` excludes later commits from review.
@@ -656,3 +897,5048 @@ fn test_review_with_stop_at_before_skip_to() {
         stderr
     );
 }
+
+/// Test that `cresca abort` switches back to the target branch and deletes the review branch.
+#[test]
+fn test_abort_deletes_review_branch() {
+    let repo = TempGitRepo::new();
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Stage part of the changes to make sure they are discarded too
+    repo.git(&["add", "feature.txt"]);
+
+    // Run abort
+    let output = repo.run_cresca(&["abort"]);
+    assert!(
+        output.status.success(),
+        "cresca abort should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: back on main with a clean working directory
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Working directory should be clean after abort"
+    );
+
+    // Verify: review branch is deleted
+    let branches = repo.git(&["branch", "--list", "review-main-develop"]);
+    assert!(
+        branches.stdout.is_empty(),
+        "review-main-develop should be deleted"
+    );
+
+    // Verify: review metadata is removed from git config
+    let config = repo.git(&["config", "--list"]);
+    assert!(
+        !String::from_utf8_lossy(&config.stdout).contains("cresca.review-main-develop"),
+        "Review metadata should be removed from git config"
+    );
+}
+
+/// Test that `cresca abort` falls back to the default branch when the target branch is gone.
+#[test]
+fn test_abort_with_deleted_to_branch() {
+    let repo = TempGitRepo::new();
+
+    // Create a target branch and a develop branch on top of it
+    repo.create_branch("target");
+    repo.git(&["push", "-u", "origin", "target"]);
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Run review, then delete the target branch
+    repo.switch_branch("target");
+    repo.run_cresca(&["review", "target", "develop"]);
+    repo.git(&["branch", "-D", "target"]);
+
+    // Run abort
+    let output = repo.run_cresca(&["abort"]);
+    assert!(
+        output.status.success(),
+        "cresca abort should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: switched to the default branch
+    assert_eq!(repo.current_branch(), "main");
+}
+
+/// Test that `cresca abort` falls back to the default branch of the configured remote.
+#[test]
+fn test_abort_with_deleted_to_branch_configured_remote() {
+    let repo = TempGitRepo::new();
+
+    // The default branch of upstream is trunk, while origin does not know its HEAD
+    repo.create_branch("trunk");
+    let remote_path = repo.remote_dir.path().to_str().unwrap().to_string();
+    repo.git(&["remote", "add", "upstream", &remote_path]);
+    repo.git(&["update-ref", "refs/remotes/upstream/trunk", "trunk"]);
+    repo.git(&[
+        "symbolic-ref",
+        "refs/remotes/upstream/HEAD",
+        "refs/remotes/upstream/trunk",
+    ]);
+    repo.git(&["config", "cresca.remote", "upstream"]);
+
+    repo.create_branch("target");
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.switch_branch("target");
+    repo.run_cresca(&["review", "target", "develop", "--no-pull"]);
+    repo.git(&["branch", "-D", "target"]);
+
+    let output = repo.run_cresca(&["abort"]);
+    assert!(
+        output.status.success(),
+        "cresca abort should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "trunk");
+}
+
+/// Test that `cresca abort` fails on a non-review branch.
+#[test]
+fn test_abort_on_non_review_branch() {
+    let repo = TempGitRepo::new();
+
+    // Try to abort on main (not a review branch)
+    let output = repo.run_cresca(&["abort"]);
+
+    assert!(
+        !output.status.success(),
+        "cresca abort should fail on non-review branch"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error") || stderr.contains("Not on a review branch"),
+        "Should show error message about not being on review branch"
+    );
+}
+
+/// Test that `cresca status` works when the target branch name contains a hyphen.
+#[test]
+fn test_status_with_hyphenated_to_branch() {
+    let repo = TempGitRepo::new();
+
+    // Create a release-2024 branch and a develop branch on top of it
+    repo.create_branch("release-2024");
+    repo.git(&["push", "-u", "origin", "release-2024"]);
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Run review against release-2024
+    repo.switch_branch("release-2024");
+    repo.run_cresca(&["review", "release-2024", "develop"]);
+
+    // Verify: review metadata is stored in git config
+    let to = repo.git(&["config", "--get", "cresca.review-release%2D2024-develop.to"]);
+    assert_eq!(String::from_utf8_lossy(&to.stdout).trim(), "release-2024");
+    let from = repo.git(&[
+        "config",
+        "--get",
+        "cresca.review-release%2D2024-develop.from",
+    ]);
+    assert_eq!(String::from_utf8_lossy(&from.stdout).trim(), "develop");
+
+    // Run status
+    let output = repo.run_cresca(&["status"]);
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Remaining diff to develop"),
+        "Should compare against develop, got: {}",
+        stdout
+    );
+    assert!(stdout.contains("feature.txt"), "Should list feature.txt");
+}
+
+/// Test that review branch names round-trip when both branches contain multiple hyphens.
+#[test]
+fn test_review_with_hyphenated_branch_names() {
+    let repo = TempGitRepo::new();
+
+    // Create a release-1.0-rc branch and a feature-x-y branch on top of it
+    repo.create_branch("release-1.0-rc");
+    repo.git(&["push", "-u", "origin", "release-1.0-rc"]);
+    repo.create_branch("feature-x-y");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "feature-x-y"]);
+
+    // Run review
+    repo.switch_branch("release-1.0-rc");
+    let output = repo.run_cresca(&["review", "release-1.0-rc", "feature-x-y"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: hyphens in the branch names are encoded
+    let review_branch = repo.current_branch();
+    assert_eq!(review_branch, "review-release%2D1.0%2Drc-feature%2Dx%2Dy");
+
+    // Remove the stored metadata so the branch name has to be parsed
+    repo.git(&[
+        "config",
+        "--remove-section",
+        &format!("cresca.{}", review_branch),
+    ]);
+
+    // Run status
+    let output = repo.run_cresca(&["status"]);
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Remaining diff to feature-x-y"),
+        "Should compare against feature-x-y, got: {}",
+        stdout
+    );
+    assert!(stdout.contains("feature.txt"), "Should list feature.txt");
+}
+
+/// Test that `cresca review` and `cresca status` work when the development branch contains a slash.
+#[test]
+fn test_review_with_slashed_branch_name() {
+    let repo = TempGitRepo::new();
+
+    // Create a feature/login branch with some changes
+    repo.create_branch("feature/login");
+    repo.write_file("login.txt", "login feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add login");
+    repo.git(&["push", "-u", "origin", "feature/login"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "feature/login"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: the review branch is a single flat ref
+    let review_branch = repo.current_branch();
+    assert_eq!(review_branch, "review-main-feature%2Flogin");
+
+    // Run status with the stored metadata removed so the branch name has to be parsed
+    repo.git(&[
+        "config",
+        "--remove-section",
+        &format!("cresca.{}", review_branch),
+    ]);
+    let output = repo.run_cresca(&["status"]);
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Remaining diff to feature/login"),
+        "Should compare against feature/login, got: {}",
+        stdout
+    );
+    assert!(stdout.contains("login.txt"), "Should list login.txt");
+}
+
+/// Test that `cresca status --json` prints the full status as JSON.
+#[test]
+fn test_status_json_output() {
+    let repo = TempGitRepo::new();
+
+    // Create a develop branch with more files than the human-readable output shows
+    repo.create_branch("develop");
+    for i in 0..12 {
+        repo.write_file(&format!("file{}.txt", i), "content");
+    }
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Run status with --json
+    let output = repo.run_cresca(&["status", "--json"]);
+    assert!(
+        output.status.success(),
+        "cresca status --json should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !stdout.contains("\x1b["),
+        "JSON should not contain ANSI codes"
+    );
+
+    let status: serde_json::Value =
+        serde_json::from_str(&stdout).expect("status should be valid JSON");
+    assert_eq!(status["from_branch"], "develop");
+    assert_eq!(status["file_count"], 12);
+    assert_eq!(status["insertions"], 12);
+    assert_eq!(status["deletions"], 0);
+    assert_eq!(
+        status["files"].as_array().map(|files| files.len()),
+        Some(12),
+        "All files should be listed"
+    );
+}
+
+/// Test that `cresca status --json` reports errors as JSON on a non-review branch.
+#[test]
+fn test_status_json_on_non_review_branch() {
+    let repo = TempGitRepo::new();
+
+    // Try to run status on main (not a review branch)
+    let output = repo.run_cresca(&["status", "--json"]);
+
+    assert!(
+        !output.status.success(),
+        "cresca status --json should fail on non-review branch"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error: serde_json::Value =
+        serde_json::from_str(&stderr).expect("error should be valid JSON");
+    assert!(error["error"].is_string(), "Should have an error field");
+
+    let output = repo.run_cresca(&["status", "--json", "--branch", "nope"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --json should fail for an unknown branch"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error: serde_json::Value =
+        serde_json::from_str(&stderr).expect("error should be valid JSON");
+    assert!(
+        error["error"]
+            .as_str()
+            .is_some_and(|error| error.contains("nope")),
+        "Should name the unknown branch, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca status` prints no ANSI escape sequences when `NO_COLOR` is set.
+#[test]
+fn test_status_respects_no_color() {
+    let repo = TempGitRepo::new();
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Run status with NO_COLOR, even if color is forced otherwise
+    let output =
+        repo.run_cresca_with_env(&["status"], &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]);
+    assert!(output.status.success(), "cresca status should succeed");
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("\x1b["),
+        "Output should not contain ANSI escape sequences"
+    );
+
+    // Run status with --no-color
+    let output = repo.run_cresca_with_env(&["status", "--no-color"], &[("CLICOLOR_FORCE", "1")]);
+    assert!(output.status.success(), "cresca status should succeed");
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("\x1b["),
+        "Output should not contain ANSI escape sequences"
+    );
+}
+
+/// Test that `cresca review --no-pull` works without a reachable remote.
+#[test]
+fn test_review_with_no_pull_option() {
+    let repo = TempGitRepo::new();
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+
+    // Remove the remote so that pulling would fail
+    repo.switch_branch("main");
+    repo.git(&["remote", "remove", "origin"]);
+
+    // Run cresca review without pulling
+    let output = repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    assert!(
+        output.status.success(),
+        "cresca review --no-pull should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: on the review branch with the changes unstaged
+    assert_eq!(repo.current_branch(), "review-main-develop");
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("feature.txt"),
+        "feature.txt should appear in status"
+    );
+}
+
+/// Test that `cresca review --remote` pulls from the given remote.
+#[test]
+fn test_review_with_remote_option() {
+    let repo = TempGitRepo::new();
+
+    // Rename origin to upstream
+    repo.git(&["remote", "rename", "origin", "upstream"]);
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "upstream", "develop"]);
+    repo.switch_branch("main");
+
+    // Review fails with the default remote
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail without origin remote"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error") && stderr.contains("origin"),
+        "Should show error about missing remote, got: {}",
+        stderr
+    );
+
+    // Review succeeds with --remote
+    let output = repo.run_cresca(&["review", "main", "develop", "--remote", "upstream"]);
+    assert!(
+        output.status.success(),
+        "cresca review --remote should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "review-main-develop");
+}
+
+/// Test that the remote can be selected with the `cresca.remote` config and `CRESCA_REMOTE`.
+#[test]
+fn test_review_remote_from_config_and_env() {
+    let repo = TempGitRepo::new();
+
+    // Rename origin to upstream
+    repo.git(&["remote", "rename", "origin", "upstream"]);
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "upstream", "develop"]);
+    repo.switch_branch("main");
+
+    // CRESCA_REMOTE takes precedence over the config
+    repo.git(&["config", "cresca.remote", "nonexistent"]);
+    let output = repo.run_cresca_with_env(
+        &["review", "main", "develop"],
+        &[("CRESCA_REMOTE", "upstream")],
+    );
+    assert!(
+        output.status.success(),
+        "cresca review with CRESCA_REMOTE should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The config is used when no flag or env var is given
+    repo.run_cresca(&["abort"]);
+    repo.git(&["config", "cresca.remote", "upstream"]);
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review with cresca.remote config should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that `cresca approve --message` and `cresca review --auto-approve-message` set the commit messages.
+#[test]
+fn test_approve_with_custom_messages() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with two commits
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.git(&["add", "."]);
+    repo.commit("Add file1");
+
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add file2");
+
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Get the hash of the second commit
+    let log_output = repo.git(&["log", "--oneline", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let file2_hash = log_str
+        .lines()
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap();
+
+    // Review with a custom auto-approve message
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        file2_hash,
+        "--auto-approve-message",
+        "Skip \"setup\" commits",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let message = repo.git(&["log", "-1", "--format=%s"]);
+    assert_eq!(
+        String::from_utf8_lossy(&message.stdout).trim(),
+        "Skip \"setup\" commits"
+    );
+
+    // Approve with a custom message
+    repo.git(&["add", "."]);
+    let output = repo.run_cresca(&["approve", "-m", "Reviewed by Alice: file2's changes"]);
+    assert!(
+        output.status.success(),
+        "cresca approve -m should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let message = repo.git(&["log", "-1", "--format=%s"]);
+    assert_eq!(
+        String::from_utf8_lossy(&message.stdout).trim(),
+        "Reviewed by Alice: file2's changes"
+    );
+}
+
+/// Test that `cresca log` shows approved and remaining commits.
+#[test]
+fn test_log_shows_approved_and_remaining() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with a commit
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.git(&["add", "."]);
+    repo.commit("Add file1");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve all changes
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve", "-m", "Approve file1"]);
+
+    // Run log
+    let output = repo.run_cresca(&["log"]);
+    assert!(
+        output.status.success(),
+        "cresca log should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (approved, remaining) = stdout
+        .split_once("Remaining")
+        .expect("Should have a Remaining section");
+    assert!(
+        approved.contains("Approved"),
+        "Should have an Approved section"
+    );
+    assert!(
+        approved.contains("Approve file1"),
+        "Approve commit should be listed as approved, got: {}",
+        stdout
+    );
+    assert!(
+        remaining.contains("Add file1"),
+        "Development commit should be listed as remaining, got: {}",
+        stdout
+    );
+}
+
+/// Test that `cresca log` fails on a non-review branch.
+#[test]
+fn test_log_on_non_review_branch() {
+    let repo = TempGitRepo::new();
+
+    // Try to run log on main (not a review branch)
+    let output = repo.run_cresca(&["log"]);
+
+    assert!(
+        !output.status.success(),
+        "cresca log should fail on non-review branch"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error") || stderr.contains("Not on a review branch"),
+        "Should show error message about not being on review branch"
+    );
+}
+
+/// Test that `cresca diff` shows the remaining diff, optionally limited to paths or as a stat.
+#[test]
+fn test_diff_shows_remaining_changes() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with two files
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Full diff
+    let output = repo.run_cresca(&["diff"]);
+    assert!(
+        output.status.success(),
+        "cresca diff should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("+content 1"), "Should show file1.txt diff");
+    assert!(stdout.contains("+content 2"), "Should show file2.txt diff");
+
+    // Diff limited to a path
+    let output = repo.run_cresca(&["diff", "file1.txt"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("+content 1"), "Should show file1.txt diff");
+    assert!(
+        !stdout.contains("file2.txt"),
+        "Should not show file2.txt diff"
+    );
+
+    // Diff stat
+    let output = repo.run_cresca(&["diff", "--stat"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("2 files changed"),
+        "Should show diff stat, got: {}",
+        stdout
+    );
+    assert!(!stdout.contains("+content 1"), "Should not show full diff");
+}
+
+/// Test that `cresca diff` fails on a non-review branch.
+#[test]
+fn test_diff_on_non_review_branch() {
+    let repo = TempGitRepo::new();
+
+    // Try to run diff on main (not a review branch)
+    let output = repo.run_cresca(&["diff"]);
+
+    assert!(
+        !output.status.success(),
+        "cresca diff should fail on non-review branch"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error") || stderr.contains("Not on a review branch"),
+        "Should show error message about not being on review branch"
+    );
+}
+
+/// Test that `cresca approve --interactive` falls back to normal approval when stdin is not a terminal.
+#[test]
+fn test_approve_interactive_without_tty() {
+    let repo = TempGitRepo::new();
+
+    // Setup: create develop with two files
+    repo.create_branch("develop");
+    repo.write_file("reviewed.txt", "reviewed content");
+    repo.write_file("not_reviewed.txt", "not reviewed content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Stage only one file
+    repo.git(&["add", "reviewed.txt"]);
+
+    // Run approve interactively with stdin not being a terminal
+    let output = repo.run_cresca(&["approve", "--interactive"]);
+    assert!(
+        output.status.success(),
+        "cresca approve --interactive should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: only the staged file is committed
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("reviewed.txt"),
+        "reviewed.txt should be committed"
+    );
+    assert!(
+        !files_str.contains("not_reviewed.txt"),
+        "not_reviewed.txt should not be committed"
+    );
+}
+
+/// Test that `cresca status --short` prints a single line.
+#[test]
+fn test_status_short_output() {
+    let repo = TempGitRepo::new();
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Switch back to main and run review
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Run status with --short
+    let output = repo.run_cresca(&["status", "--short", "--no-color"]);
+    assert!(
+        output.status.success(),
+        "cresca status --short should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "develop: 2f +2 -0\n"
+    );
+
+    // Run status with --short on a non-review branch
+    repo.run_cresca(&["abort"]);
+    let output = repo.run_cresca(&["status", "--short"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --short should fail on non-review branch"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "not on a review branch\n"
+    );
+}
+
+/// Test that `cresca status` shows the review progress.
+#[test]
+fn test_status_shows_progress() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with four lines in two files
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "line 1\nline 2\nline 3\n");
+    repo.write_file("file2.txt", "line 1\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve file1.txt
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "file1.txt"]);
+    repo.run_cresca(&["approve"]);
+
+    // Run status
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("75% reviewed (3 of 4 lines)"),
+        "Should show review progress, got: {}",
+        stdout
+    );
+
+    // JSON output includes the progress fields
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status should be valid JSON");
+    assert_eq!(status["total_insertions"], 4);
+    assert_eq!(status["total_deletions"], 0);
+    assert_eq!(status["reviewed_percent"], 75);
+}
+
+/// Test that commands requiring a review branch report a detached HEAD distinctly.
+#[test]
+fn test_commands_on_detached_head() {
+    let repo = TempGitRepo::new();
+
+    // Detach HEAD
+    repo.git(&["checkout", "--detach", "HEAD"]);
+
+    for command in ["approve", "status"] {
+        let output = repo.run_cresca(&[command]);
+        assert!(
+            !output.status.success(),
+            "cresca {} should fail on detached HEAD",
+            command
+        );
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("detached HEAD"),
+            "cresca {} should show error about detached HEAD, got: {}",
+            command,
+            stderr
+        );
+    }
+}
+
+/// Test that `cresca review --dry-run` prints the plan without modifying the repository.
+#[test]
+fn test_review_with_dry_run() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with two commits
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.git(&["add", "."]);
+    repo.commit("Add file1");
+
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add file2");
+
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Get the hash of the second commit
+    let log_output = repo.git(&["log", "--oneline", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let file2_hash = log_str
+        .lines()
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap();
+
+    // Run review in dry-run mode
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        file2_hash,
+        "--dry-run",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --dry-run should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: the plan is printed
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[would run: git checkout -b review-main-develop"),
+        "Should show branch creation, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Add file1"),
+        "Should list the auto-approved commit, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("[would run: git merge --squash"),
+        "Should show squash merge, got: {}",
+        stdout
+    );
+
+    // Verify: nothing was changed
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Working directory should be clean"
+    );
+    let branches = repo.git(&["branch", "--list", "review-main-develop"]);
+    assert!(
+        branches.stdout.is_empty(),
+        "review-main-develop should not be created"
+    );
+}
+
+/// Test that `cresca list` lists review branches and marks the current one.
+#[test]
+fn test_list_review_branches() {
+    let repo = TempGitRepo::new();
+
+    // Create two development branches
+    for branch in ["develop", "feature/login"] {
+        repo.switch_branch("main");
+        repo.create_branch(branch);
+        repo.write_file(&format!("{}.txt", branch.replace('/', "-")), "content");
+        repo.git(&["add", "."]);
+        repo.commit("Add feature");
+        repo.git(&["push", "-u", "origin", branch]);
+    }
+
+    // Review both branches, leaving the second review branch checked out
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+    repo.run_cresca(&["review", "main", "feature/login"]);
+
+    // Run list
+    let output = repo.run_cresca(&["list"]);
+    assert!(
+        output.status.success(),
+        "cresca list should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "Should list two branches, got: {}", stdout);
+    assert!(
+        lines[0].starts_with("  review-main-develop") && lines[0].contains("main <- develop"),
+        "Should list review-main-develop, got: {}",
+        lines[0]
+    );
+    assert!(
+        lines[1].starts_with("* review-main-feature%2Flogin")
+            && lines[1].contains("main <- feature/login"),
+        "Should list review-main-feature%2Flogin as current, got: {}",
+        lines[1]
+    );
+}
+
+/// Test that `cresca switch` checks out an existing review branch by its branches.
+#[test]
+fn test_switch_to_review_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("feature/login");
+    repo.write_file("login.txt", "content");
+    repo.git(&["add", "."]);
+    repo.commit("Add login");
+    repo.git(&["push", "-u", "origin", "feature/login"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["switch", "main", "feature/login"]);
+    assert!(
+        !output.status.success(),
+        "cresca switch should fail without a review branch"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("cresca review main feature/login"),
+        "Should suggest running cresca review, got: {}",
+        stderr
+    );
+
+    repo.run_cresca(&["review", "main", "feature/login"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+    repo.switch_branch("main");
+
+    // Uncommitted changes are not carried onto the review branch
+    repo.write_file("README.md", "# Local edit");
+    let output = repo.run_cresca(&["switch", "main", "feature/login"]);
+    assert!(
+        !output.status.success(),
+        "cresca switch should fail with uncommitted changes"
+    );
+    assert_eq!(repo.current_branch(), "main");
+    repo.git(&["checkout", "--", "README.md"]);
+
+    let output = repo.run_cresca(&["switch", "main", "feature/login"]);
+    assert!(
+        output.status.success(),
+        "cresca switch should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "review-main-feature%2Flogin");
+}
+
+/// Test that `cresca clean` deletes review branches of merged or deleted branches.
+#[test]
+fn test_clean_deletes_done_review_branches() {
+    let repo = TempGitRepo::new();
+
+    // Create three development branches
+    for branch in ["merged", "deleted", "unmerged"] {
+        repo.switch_branch("main");
+        repo.create_branch(branch);
+        repo.write_file(&format!("{}.txt", branch), "content");
+        repo.git(&["add", "."]);
+        repo.commit("Add feature");
+        repo.git(&["push", "-u", "origin", branch]);
+    }
+
+    // Review and fully approve each branch
+    repo.switch_branch("main");
+    for branch in ["merged", "deleted", "unmerged"] {
+        repo.run_cresca(&["review", "main", branch]);
+        repo.git(&["add", "."]);
+        repo.run_cresca(&["approve"]);
+    }
+
+    // Merge one branch and delete another
+    repo.switch_branch("main");
+    repo.git(&["merge", "--quiet", "merged"]);
+    repo.git(&["branch", "-D", "deleted"]);
+
+    // Run clean
+    let output = repo.run_cresca(&["clean"]);
+    assert!(
+        output.status.success(),
+        "cresca clean should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("2 review branch(es) deleted"),
+        "Should delete two branches, got: {}",
+        stdout
+    );
+
+    // Verify: only the review branch of the unmerged branch remains
+    let branches = repo.git(&["branch", "--list", "review-*", "--format=%(refname:short)"]);
+    assert_eq!(
+        String::from_utf8_lossy(&branches.stdout).trim(),
+        "review-main-unmerged"
+    );
+}
+
+/// Test that `cresca clean` requires `--force` for review branches with unapproved changes.
+#[test]
+fn test_clean_requires_force_for_unapproved_changes() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with two files
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve only one file
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "file1.txt"]);
+    repo.run_cresca(&["approve"]);
+
+    // Merge develop into main
+    repo.switch_branch("main");
+    repo.git(&["merge", "--quiet", "develop"]);
+
+    // Clean without --force skips the branch
+    let output = repo.run_cresca(&["clean"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "cresca clean should succeed");
+    assert!(
+        stdout.contains("Skipped review-main-develop"),
+        "Should skip the branch, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("0 review branch(es) deleted"),
+        "Should delete nothing, got: {}",
+        stdout
+    );
+
+    // Clean with --force deletes the branch
+    let output = repo.run_cresca(&["clean", "--force"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca clean --force should succeed"
+    );
+    assert!(
+        stdout.contains("Deleted review-main-develop"),
+        "Should delete the branch, got: {}",
+        stdout
+    );
+}
+
+/// Test that `.cresca.toml` settings are used by `cresca review` and `cresca approve`.
+#[test]
+fn test_repo_config_file() {
+    let repo = TempGitRepo::new();
+
+    // Write repo and global configs; the repo config takes precedence
+    repo.write_global_config("default_to = \"nonexistent\"\nprefix = \"global\"\n");
+    repo.write_file(
+        ".cresca.toml",
+        "default_to = \"main\"\nprefix = \"cr\"\napprove_message = \"Reviewed\"\n",
+    );
+    repo.git(&["add", "."]);
+    repo.commit("Add cresca config");
+    repo.git(&["push", "origin", "main"]);
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    // Run review with only the development branch
+    let output = repo.run_cresca(&["review", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "cr-main-develop");
+
+    // Run status on the review branch with the custom prefix
+    let output = repo.run_cresca(&["status"]);
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Approve with the configured message
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+    let message = repo.git(&["log", "-1", "--format=%s"]);
+    assert_eq!(String::from_utf8_lossy(&message.stdout).trim(), "Reviewed");
+}
+
+/// Test that the remote in the global config is used when no other setting is given.
+#[test]
+fn test_global_config_remote() {
+    let repo = TempGitRepo::new();
+
+    // Rename origin to upstream
+    repo.git(&["remote", "rename", "origin", "upstream"]);
+
+    // Create a develop branch with some changes
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "upstream", "develop"]);
+    repo.switch_branch("main");
+
+    // Run review with the remote from the global config
+    repo.write_global_config("remote = \"upstream\"\n");
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that a malformed config file is reported as an error.
+#[test]
+fn test_malformed_config_file() {
+    let repo = TempGitRepo::new();
+
+    repo.write_global_config("default_to = ");
+
+    let output = repo.run_cresca(&["list"]);
+    assert!(
+        !output.status.success(),
+        "cresca should fail with a malformed config file"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error") && stderr.contains("config.toml"),
+        "Should show error about the config file, got: {}",
+        stderr
+    );
+    assert!(!stderr.contains("panicked"), "Should not panic");
+}
+
+/// Test that `cresca review --strategy-option` is passed to the squash merge.
+#[test]
+fn test_review_with_strategy_option() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch that modifies README.md
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Develop");
+    repo.git(&["add", "."]);
+    repo.commit("Update README on develop");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve the change
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+
+    // Make a conflicting change on develop
+    repo.switch_branch("develop");
+    repo.write_file("README.md", "# Develop 2");
+    repo.git(&["add", "."]);
+    repo.commit("Update README again");
+    repo.git(&["push", "origin", "develop"]);
+
+    // Make a conflicting change on the review branch
+    repo.switch_branch("review-main-develop");
+    repo.write_file("README.md", "# Review");
+    repo.git(&["add", "."]);
+    repo.commit("Local change on review branch");
+
+    // Review with the ours strategy option keeps the review branch version
+    let output = repo.run_cresca(&["review", "main", "develop", "-X", "ours"]);
+    assert!(
+        output.status.success(),
+        "cresca review -X ours should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = std::fs::read_to_string(repo.path().join("README.md")).unwrap();
+    assert_eq!(content, "# Review");
+}
+
+/// Test that `cresca review --detect-conflicts` stops and lists conflicted files.
+#[test]
+fn test_review_with_detect_conflicts() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch that modifies README.md
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Develop");
+    repo.git(&["add", "."]);
+    repo.commit("Update README on develop");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve the change
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+
+    // Make a conflicting change on develop
+    repo.switch_branch("develop");
+    repo.write_file("README.md", "# Develop 2");
+    repo.git(&["add", "."]);
+    repo.commit("Update README again");
+    repo.git(&["push", "origin", "develop"]);
+
+    // Make a conflicting change on the review branch
+    repo.switch_branch("review-main-develop");
+    repo.write_file("README.md", "# Review");
+    repo.git(&["add", "."]);
+    repo.commit("Local change on review branch");
+
+    // Review with conflict detection stops on the conflict
+    let output = repo.run_cresca(&["review", "main", "develop", "--detect-conflicts"]);
+    assert!(
+        !output.status.success(),
+        "cresca review --detect-conflicts should fail on conflicts"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Merge conflicts found") && stderr.contains("README.md"),
+        "Should list the conflicted file, got: {}",
+        stderr
+    );
+    let content = std::fs::read_to_string(repo.path().join("README.md")).unwrap();
+    assert!(
+        content.contains("<<<<<<<"),
+        "Conflict markers should be left in the working tree"
+    );
+}
+
+/// Test that `cresca completions bash` generates a completion script.
+#[test]
+fn test_completions_bash() {
+    let repo = TempGitRepo::new();
+
+    let output = repo.run_cresca(&["completions", "bash"]);
+    assert!(
+        output.status.success(),
+        "cresca completions should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for subcommand in ["review", "approve", "status"] {
+        assert!(
+            stdout.contains(subcommand),
+            "Completion script should mention {}, got: {}",
+            subcommand,
+            stdout
+        );
+    }
+}
+
+/// Test that `cresca __complete` suggests branches and the commits of the review.
+#[test]
+fn test_complete_branches_and_commits() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    let hash = String::from_utf8_lossy(&repo.git(&["rev-parse", "HEAD"]).stdout)
+        .trim()
+        .to_string();
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["__complete", "--", "review", "main", "dev"]);
+    assert!(
+        output.status.success(),
+        "cresca __complete should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "develop\n");
+
+    // The values of options are not taken for branches
+    let output = repo.run_cresca(&[
+        "__complete",
+        "--",
+        "review",
+        "--path",
+        "src",
+        "--exclude",
+        "*.lock",
+        "main",
+        "dev",
+    ]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "develop\n");
+
+    let output = repo.run_cresca(&[
+        "__complete",
+        "--",
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        "",
+    ]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{}\n", hash)
+    );
+
+    // Other words are left to the static completion
+    let output = repo.run_cresca(&["__complete", "--", "status", ""]);
+    assert!(
+        output.stdout.is_empty(),
+        "No candidates should be printed for other subcommands"
+    );
+
+    let output = repo.run_cresca(&["completions", "bash"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("cresca __complete"),
+        "Completion script should call cresca __complete"
+    );
+}
+
+/// Test that `cresca undo` moves the most recent approval back to unreviewed changes.
+#[test]
+fn test_undo_reverts_last_approval() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("first.txt", "first content");
+    repo.write_file("second.txt", "second content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Approve the files one by one
+    repo.git(&["add", "first.txt"]);
+    repo.run_cresca(&["approve", "-m", "Approve first"]);
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "second.txt"]);
+    repo.run_cresca(&["approve", "-m", "Approve second"]);
+
+    let output = repo.run_cresca(&["undo"]);
+    assert!(
+        output.status.success(),
+        "cresca undo should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Approve second"),
+        "Should print the undone commit, got: {}",
+        stdout
+    );
+
+    // Verify: second.txt is an unstaged change again, first.txt stays approved
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("?? second.txt"),
+        "second.txt should be an unstaged change, got: {}",
+        status_str
+    );
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("first.txt") && !files_str.contains("second.txt"),
+        "Only first.txt should remain approved, got: {}",
+        files_str
+    );
+}
+
+/// Test that `cresca undo` fails when there is no approval to undo.
+#[test]
+fn test_undo_without_approvals() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("file1.txt", "content 1");
+    repo.git(&["add", "."]);
+    repo.commit("Add file1");
+    repo.write_file("file2.txt", "content 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add file2");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    let log_output = repo.git(&["log", "--oneline", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let file2_hash = log_str
+        .lines()
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap();
+
+    repo.switch_branch("main");
+
+    // Without approvals
+    repo.run_cresca(&["review", "main", "develop"]);
+    let output = repo.run_cresca(&["undo"]);
+    assert!(
+        !output.status.success(),
+        "cresca undo should fail without approvals"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Nothing to undo"),
+        "Should show error about nothing to undo, got: {}",
+        stderr
+    );
+    repo.run_cresca(&["abort"]);
+
+    // Only the auto-approve commit
+    repo.run_cresca(&["review", "main", "develop", "--skip-to", file2_hash]);
+    let output = repo.run_cresca(&["undo"]);
+    assert!(
+        !output.status.success(),
+        "cresca undo should not undo the auto-approve commit"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("auto-approve"),
+        "Should show error about the auto-approve commit, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca continue` finishes the review after conflicts are resolved.
+#[test]
+fn test_continue_after_resolving_conflicts() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch that modifies README.md
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Develop");
+    repo.git(&["add", "."]);
+    repo.commit("Update README on develop");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Review and approve the change
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+
+    // Nothing to continue without conflicts
+    let output = repo.run_cresca(&["continue"]);
+    assert!(
+        !output.status.success(),
+        "cresca continue should fail without conflicts"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Nothing to continue"),
+        "Should show error about nothing to continue, got: {}",
+        stderr
+    );
+
+    // Make conflicting changes on develop and the review branch
+    repo.switch_branch("develop");
+    repo.write_file("README.md", "# Develop 2");
+    repo.git(&["add", "."]);
+    repo.commit("Update README again");
+    repo.git(&["push", "origin", "develop"]);
+
+    repo.switch_branch("review-main-develop");
+    repo.write_file("README.md", "# Review");
+    repo.git(&["add", "."]);
+    repo.commit("Local change on review branch");
+
+    repo.run_cresca(&["review", "main", "develop", "--detect-conflicts"]);
+
+    // Continuing with unresolved conflicts fails
+    let output = repo.run_cresca(&["continue"]);
+    assert!(
+        !output.status.success(),
+        "cresca continue should fail with unresolved conflicts"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Unresolved conflicts") && stderr.contains("README.md"),
+        "Should list the unresolved file, got: {}",
+        stderr
+    );
+
+    // Resolve the conflict and continue
+    repo.write_file("README.md", "# Resolved");
+    repo.git(&["add", "README.md"]);
+    let output = repo.run_cresca(&["continue"]);
+    assert!(
+        output.status.success(),
+        "cresca continue should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: the resolved change is an unstaged change for review
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains(" M README.md"),
+        "README.md should be an unstaged change, got: {}",
+        status_str
+    );
+}
+
+/// Test that `cresca continue` squash merges the remaining changes with the strategy options of the review.
+#[test]
+fn test_continue_keeps_strategy_options() {
+    let repo = TempGitRepo::new();
+
+    repo.write_file("x.txt", "x");
+    repo.git(&["add", "."]);
+    repo.commit("Add x");
+    repo.git(&["push", "origin", "main"]);
+
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Develop");
+    repo.git(&["add", "."]);
+    repo.commit("Update README on develop");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve"]);
+
+    // The review branch deletes x.txt and changes README.md, which conflict with develop
+    repo.write_file("README.md", "# Review");
+    repo.git(&["rm", "--quiet", "x.txt"]);
+    repo.git(&["add", "."]);
+    repo.commit("Local change on review branch");
+
+    repo.switch_branch("develop");
+    repo.write_file("x.txt", "x 2");
+    repo.write_file("y.txt", "y");
+    repo.git(&["add", "."]);
+    repo.commit("Update x and add y");
+    repo.write_file("README.md", "# Develop 2");
+    repo.git(&["rm", "--quiet", "x.txt"]);
+    repo.git(&["add", "."]);
+    repo.commit("Update README again and remove x");
+    repo.git(&["push", "origin", "develop"]);
+    let skip_to = repo.git(&["rev-parse", "HEAD"]);
+    let skip_to = String::from_utf8_lossy(&skip_to.stdout).trim().to_string();
+
+    // `-X ours` cannot resolve the modify/delete conflict of the auto-approve merge
+    repo.switch_branch("review-main-develop");
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        &skip_to,
+        "-X",
+        "ours",
+    ]);
+    assert!(
+        !output.status.success(),
+        "cresca review should stop on the modify/delete conflict"
+    );
+
+    // After continuing, `-X ours` resolves the README.md conflict of the remaining changes
+    repo.git(&["rm", "--quiet", "x.txt"]);
+    let output = repo.run_cresca(&["continue"]);
+    assert!(
+        output.status.success(),
+        "cresca continue should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = std::fs::read_to_string(repo.path().join("README.md")).unwrap();
+    assert_eq!(
+        content, "# Review",
+        "The review branch side should win with -X ours"
+    );
+}
+
+/// Test that `cresca approve --all` commits all remaining changes.
+#[test]
+fn test_approve_all() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Updated");
+    repo.write_file("new_file.txt", "new content");
+    repo.git(&["add", "."]);
+    repo.commit("Update files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["approve", "--all"]);
+    assert!(
+        output.status.success(),
+        "cresca approve --all should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Verify: all files are committed and the working directory is clean
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("new_file.txt"),
+        "new_file.txt should be committed"
+    );
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Working directory should be clean after approve --all"
+    );
+    let diff = repo.git(&["diff", "HEAD", "develop"]);
+    assert!(
+        diff.stdout.is_empty(),
+        "No diff should remain to develop, got: {}",
+        String::from_utf8_lossy(&diff.stdout)
+    );
+}
+
+/// Test that `cresca approve --allow-empty` creates an empty approve commit when nothing is staged.
+#[test]
+fn test_approve_allow_empty() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&[
+        "approve",
+        "--allow-empty",
+        "-y",
+        "-m",
+        "Reviewed, nothing to approve",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca approve --allow-empty should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = repo.git(&["log", "--format=%s", "main..HEAD"]);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout),
+        "Reviewed, nothing to approve\n"
+    );
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    assert!(
+        !String::from_utf8_lossy(&files_in_head.stdout).contains("feature.txt"),
+        "The empty approve commit should not contain unstaged changes"
+    );
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Unreviewed changes should be discarded"
+    );
+}
+
+/// Test that `cresca add` and `cresca reset` stage and unstage files on a review branch.
+#[test]
+fn test_add_and_reset() {
+    let repo = TempGitRepo::new();
+
+    let output = repo.run_cresca(&["add", "."]);
+    assert!(
+        !output.status.success(),
+        "cresca add should fail outside of a review branch"
+    );
+
+    repo.create_branch("develop");
+    repo.write_file("first.txt", "first content");
+    repo.write_file("second.txt", "second content");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["add", "."]);
+    assert!(
+        output.status.success(),
+        "cresca add should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("2 file(s) staged for approval"),
+        "Should print the number of staged files, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = repo.run_cresca(&["reset", "second*"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("1 file(s) staged for approval"),
+        "Should print the number of staged files, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let staged = repo.git(&["diff", "--cached", "--name-only"]);
+    assert_eq!(String::from_utf8_lossy(&staged.stdout), "first.txt\n");
+}
+
+/// Test that `cresca approve --from-stdin` approves exactly the piped files.
+#[test]
+fn test_approve_from_stdin() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for name in ["first.txt", "second.txt", "third.txt"] {
+        repo.write_file(name, "content");
+    }
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca_with_input(
+        &["approve", "--from-stdin"],
+        "first.txt\nthird.txt\nunknown.txt\n",
+    );
+    assert!(
+        output.status.success(),
+        "cresca approve --from-stdin should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown.txt"),
+        "Should warn about the file without changes, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("first.txt") && files_str.contains("third.txt"),
+        "The piped files should be approved, got: {}",
+        files_str
+    );
+    assert!(
+        !files_str.contains("second.txt"),
+        "Other files should not be approved"
+    );
+}
+
+#[test]
+fn test_status_file_limit() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for i in 0..12 {
+        repo.write_file(&format!("file{:02}.txt", i), "content");
+    }
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Default limit of 10 files
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("file09.txt") && !stdout.contains("file10.txt"),
+        "Should list 10 files by default, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("and 2 more files"),
+        "Should show the number of omitted files, got: {}",
+        stdout
+    );
+
+    let output = repo.run_cresca(&["status", "--limit", "3"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("file02.txt") && !stdout.contains("file03.txt"),
+        "Should list 3 files with --limit 3, got: {}",
+        stdout
+    );
+
+    let output = repo.run_cresca(&["status", "--all"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("file11.txt") && !stdout.contains("more file"),
+        "Should list all files with --all, got: {}",
+        stdout
+    );
+}
+
+/// Test that `cresca review --pr` resolves the branches with gh and fetches the PR head.
+#[test]
+#[cfg(all(unix, feature = "github"))]
+fn test_review_pull_request() {
+    let repo = TempGitRepo::new();
+
+    // Create the PR head only on the remote, as for a PR from a fork
+    repo.create_branch("contributor");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "origin", "contributor:refs/pull/7/head"]);
+    repo.switch_branch("main");
+    repo.git(&["branch", "-D", "contributor"]);
+
+    let path = repo.write_fake_command(
+        "gh",
+        "#!/bin/sh\necho '{\"baseRefName\":\"main\",\"headRefName\":\"feature\"}'\n",
+    );
+    let output = repo.run_cresca_with_env(&["review", "--pr", "7"], &[("PATH", &path)]);
+    assert!(
+        output.status.success(),
+        "cresca review --pr should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(repo.current_branch(), "review-main-feature");
+    let status = repo.run_cresca(&["status"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("feature.txt"),
+        "feature.txt should appear in status, got: {}",
+        status_str
+    );
+}
+
+/// Test that `cresca review --pr` leaves a local branch named like the PR head alone.
+#[test]
+#[cfg(all(unix, feature = "github"))]
+fn test_review_pull_request_keeps_local_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("contributor");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "origin", "contributor:refs/pull/7/head"]);
+    repo.switch_branch("main");
+    repo.git(&["branch", "-D", "contributor"]);
+
+    // A local branch of the same name with unpushed work
+    repo.create_branch("feature");
+    repo.write_file("local.txt", "unpushed work");
+    repo.git(&["add", "."]);
+    repo.commit("Unpushed work");
+    let local_head = repo.git(&["rev-parse", "feature"]).stdout;
+    repo.switch_branch("main");
+
+    let path = repo.write_fake_command(
+        "gh",
+        "#!/bin/sh\necho '{\"baseRefName\":\"main\",\"headRefName\":\"feature\"}'\n",
+    );
+    let output = repo.run_cresca_with_env(&["review", "--pr", "7"], &[("PATH", &path)]);
+    assert!(
+        output.status.success(),
+        "cresca review --pr should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        repo.git(&["rev-parse", "feature"]).stdout,
+        local_head,
+        "The local branch should not be overwritten"
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("feature.txt") && !status_str.contains("local.txt"),
+        "Only the PR head should be reviewed, got: {}",
+        status_str
+    );
+    let list = repo.run_cresca(&["list"]);
+    let list_str = String::from_utf8_lossy(&list.stdout);
+    assert!(
+        list_str.contains("main <- feature"),
+        "The PR head branch should be listed, got: {}",
+        list_str
+    );
+}
+
+/// Test that `cresca review --pr` reports a missing gh.
+#[test]
+#[cfg(all(unix, feature = "github"))]
+fn test_review_pull_request_without_gh() {
+    let repo = TempGitRepo::new();
+
+    let path = repo.git_only_path();
+    let output = repo.run_cresca_with_env(&["review", "--pr", "7"], &[("PATH", &path)]);
+    assert!(
+        !output.status.success(),
+        "cresca review --pr should fail without gh"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("gh") && stderr.contains("not installed"),
+        "Should show error about gh not being installed, got: {}",
+        stderr
+    );
+}
+
+/// Test that a missing git executable is reported with a friendly error.
+#[test]
+fn test_git_not_found() {
+    let repo = TempGitRepo::new();
+
+    let output = repo.run_cresca_with_env(&["list"], &[("PATH", "")]);
+    assert!(
+        !output.status.success(),
+        "cresca should fail without git on PATH"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("git executable git not found") && stderr.contains("CRESCA_GIT"),
+        "Should show error about the missing git executable, got: {}",
+        stderr
+    );
+    assert!(!stderr.contains("panicked"), "Should not panic");
+    assert_eq!(
+        output.status.code(),
+        Some(127),
+        "Should exit like a shell command that is not found"
+    );
+}
+
+/// Test that `CRESCA_GIT` and `--git-binary` select the git executable.
+#[test]
+fn test_git_binary_option() {
+    let repo = TempGitRepo::new();
+
+    let git = TempGitRepo::git_executable();
+    let git = git.to_str().unwrap();
+
+    let output = repo.run_cresca_with_env(&["list"], &[("PATH", ""), ("CRESCA_GIT", git)]);
+    assert!(
+        output.status.success(),
+        "cresca list with CRESCA_GIT should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.run_cresca_with_env(&["--git-binary", git, "list"], &[("PATH", "")]);
+    assert!(
+        output.status.success(),
+        "cresca list --git-binary should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.run_cresca(&["--git-binary", "/nonexistent/git", "list"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success() && stderr.contains("/nonexistent/git not found"),
+        "Should show error about the missing git executable, got: {}",
+        stderr
+    );
+}
+
+/// Test that running cresca outside a git repository shows a clear error.
+#[test]
+fn test_outside_git_repository() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let parent = dir.path().parent().unwrap();
+
+    let output = std::process::Command::new(TempGitRepo::cresca_binary())
+        .arg("status")
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("GIT_CEILING_DIRECTORIES", parent)
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute cresca");
+    assert!(
+        !output.status.success(),
+        "cresca status should fail outside a git repository"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Not a git repository"),
+        "Should show error about not being in a git repository, got: {}",
+        stderr
+    );
+    assert!(
+        !stderr.contains("Original error from git"),
+        "Should not show the raw git error, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca review` rejects the same branch as the target and the development branch.
+#[test]
+fn test_review_same_branch() {
+    let repo = TempGitRepo::new();
+
+    let output = repo.run_cresca(&["review", "main", "main"]);
+    assert!(
+        !output.status.success(),
+        "cresca review main main should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("are the same"),
+        "Should show error about the same branches, got: {}",
+        stderr
+    );
+
+    // A branch pointing to the same commit under a different name
+    repo.create_branch("develop");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail for branches pointing to the same commit"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("same commit"),
+        "Should show error about the same commit, got: {}",
+        stderr
+    );
+    assert_eq!(repo.current_branch(), "main");
+}
+
+/// Test that `cresca status` reports a deleted development branch.
+#[test]
+fn test_status_with_deleted_from_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["branch", "-D", "develop"]);
+
+    let output = repo.run_cresca(&["status"]);
+    assert!(
+        !output.status.success(),
+        "cresca status should fail when the development branch is deleted"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("develop no longer exists") && stderr.contains("cresca abort"),
+        "Should show error about the deleted branch, got: {}",
+        stderr
+    );
+    assert!(
+        !stderr.contains("Original error from git"),
+        "Should not show the raw git error, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca status` shows a renamed file as a single entry.
+#[test]
+fn test_status_shows_renames() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.git(&["mv", "README.md", "GUIDE.md"]);
+    repo.commit("Rename README");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&output.stdout))
+        .expect("status should be valid JSON");
+    assert_eq!(status["file_count"], 1);
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["README.md -> GUIDE.md"])
+    );
+
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("- README.md -> GUIDE.md"),
+        "Should show the rename in arrow form, got: {}",
+        stdout
+    );
+}
+
+/// Test that `cresca status` counts binary files.
+#[test]
+fn test_status_counts_binary_files() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    std::fs::write(repo.path().join("image.bin"), [0u8, 159, 146, 150, 0, 1, 2]).unwrap();
+    repo.write_file("notes.txt", "line 1\nline 2\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add binary and text files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&output.stdout))
+        .expect("status should be valid JSON");
+    assert_eq!(status["file_count"], 2);
+    assert_eq!(status["binary_files"], 1);
+    assert_eq!(status["insertions"], 2);
+    assert_eq!(status["deletions"], 0);
+
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("2 files (1 binary)"),
+        "Should show the binary file count, got: {}",
+        stdout
+    );
+}
+
+/// Test that `cresca status` counts files with long paths, which `git diff --stat` truncates.
+#[test]
+fn test_status_with_long_path() {
+    let repo = TempGitRepo::new();
+
+    let long_path = "a/deeply/nested/directory/structure/that/exceeds/fifty/characters/file.txt";
+    assert!(long_path.len() > 50);
+
+    repo.create_branch("develop");
+    repo.write_file(long_path, "line 1\nline 2\nline 3\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add deeply nested file");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&output.stdout))
+        .expect("status should be valid JSON");
+    assert_eq!(status["file_count"], 1);
+    assert_eq!(status["insertions"], 3);
+    assert_eq!(status["files"], serde_json::json!([long_path]));
+}
+
+/// Test that `cresca status` works in a non-English locale.
+#[test]
+fn test_status_with_localized_environment() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "line 1\nline 2\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca_with_env(
+        &["status", "--json"],
+        &[("LANG", "fr_FR.UTF-8"), ("LC_ALL", "fr_FR.UTF-8")],
+    );
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&output.stdout))
+        .expect("status should be valid JSON");
+    assert_eq!(status["file_count"], 1);
+    assert_eq!(status["insertions"], 2);
+}
+
+/// Test that `cresca review --skip-to` rejects a short hash matching several commits.
+#[test]
+fn test_review_with_ambiguous_skip_to() {
+    let repo = TempGitRepo::new();
+
+    // With 17 commits, at least two hashes share the first character
+    repo.create_branch("develop");
+    for i in 0..17 {
+        repo.write_file(&format!("file{}.txt", i), "content");
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    let log_output = repo.git(&["rev-list", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let hashes: Vec<&str> = log_str.lines().collect();
+    let prefix = hashes
+        .iter()
+        .map(|hash| &hash[..1])
+        .find(|prefix| {
+            hashes
+                .iter()
+                .filter(|hash| hash.starts_with(prefix))
+                .count()
+                > 1
+        })
+        .unwrap();
+
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", prefix]);
+    assert!(
+        !output.status.success(),
+        "cresca review --skip-to with an ambiguous hash should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("Ambiguous commit `{}`", prefix)),
+        "Should show error about the ambiguous hash, got: {}",
+        stderr
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    // A full hash is unambiguous
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", hashes[1]]);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to with a full hash should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert_eq!(
+        status_str.lines().count(),
+        2,
+        "Only the last two commits should be unreviewed, got: {}",
+        status_str
+    );
+}
+
+/// Test that `cresca approve --yes` discards unreviewed changes without asking.
+#[test]
+fn test_approve_with_yes() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("reviewed.txt", "reviewed content");
+    repo.write_file("not_reviewed.txt", "not reviewed content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "reviewed.txt"]);
+
+    let output = repo.run_cresca(&["approve", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca approve --yes should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !stdout.contains("Continue?"),
+        "Should not ask for confirmation, got: {}",
+        stdout
+    );
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Working directory should be clean after approve"
+    );
+}
+
+/// Test that `--quiet` suppresses informational output but keeps errors
+#[test]
+fn test_quiet() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["-q", "list"]);
+    assert!(
+        output.status.success() && output.stdout.is_empty(),
+        "cresca -q list should print nothing without review branches, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = repo.run_cresca(&["--quiet", "review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca --quiet review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        output.stdout.is_empty(),
+        "Should print nothing, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = repo.run_cresca(&["status", "-q"]);
+    assert!(output.status.success(), "cresca status -q should succeed");
+    assert!(
+        output.stdout.is_empty(),
+        "Should print nothing, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = repo.run_cresca(&["status", "-q", "--short"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("develop: 1f"),
+        "Machine-readable output should still be printed, got: {}",
+        stdout
+    );
+
+    repo.git(&["add", "feature.txt"]);
+    let output = repo.run_cresca(&["-q", "approve"]);
+    assert!(output.status.success(), "cresca -q approve should succeed");
+    assert!(
+        output.stdout.is_empty(),
+        "Should print nothing, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["-q", "status"]);
+    assert!(!output.status.success(), "cresca -q status should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Not on a review branch"),
+        "Errors should still be printed, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca status` prints ASCII-only output with `--no-emoji` or when piped
+#[test]
+fn test_status_no_emoji() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    for output in [
+        repo.run_cresca(&["status"]),
+        repo.run_cresca(&["status", "--no-emoji"]),
+        repo.run_cresca_with_env(&["status"], &[("CRESCA_NO_EMOJI", "1")]),
+    ] {
+        assert!(
+            output.status.success(),
+            "cresca status should succeed\nstdout: {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.starts_with("Review status:"),
+            "Should start with a plain label, got: {}",
+            stdout
+        );
+        assert!(stdout.is_ascii(), "Should be ASCII-only, got: {}", stdout);
+    }
+}
+
+/// Test that `cresca status --watch` refreshes the status and exits successfully on Ctrl-C
+#[cfg(unix)]
+#[test]
+fn test_status_watch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--watch", "--json"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --watch --json should fail"
+    );
+
+    let child = std::process::Command::new(TempGitRepo::cresca_binary())
+        .args(["status", "--watch", "--interval", "1"])
+        .current_dir(repo.path())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute cresca");
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("Failed to send SIGINT");
+    let output = child.wait_with_output().expect("Failed to wait for cresca");
+
+    assert!(
+        output.status.success(),
+        "cresca status --watch should exit successfully on Ctrl-C\nstdout: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.matches("Review status").count() >= 2,
+        "Status should be refreshed, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.ends_with("\x1B[?25h"),
+        "Cursor should be restored, got: {:?}",
+        stdout
+    );
+}
+
+/// Test that `cresca approve --patch` approves only the selected hunks
+#[test]
+fn test_approve_patch() {
+    let repo = TempGitRepo::new();
+
+    let lines: Vec<String> = (1..=10).map(|i| format!("line {}", i)).collect();
+    repo.write_file("feature.txt", &(lines.join("\n") + "\n"));
+    repo.git(&["add", "."]);
+    repo.commit("Add feature.txt");
+    repo.git(&["push", "origin", "main"]);
+
+    repo.create_branch("develop");
+    let mut changed = lines.clone();
+    changed[0] = "first line changed".to_string();
+    changed[9] = "last line changed".to_string();
+    repo.write_file("feature.txt", &(changed.join("\n") + "\n"));
+    repo.write_file("new.txt", "new content\n");
+    repo.git(&["add", "."]);
+    repo.commit("Change feature.txt and add new.txt");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    // Stage the first hunk, skip the last hunk and the new file
+    let output = repo.run_cresca_with_input(&["approve", "--patch"], "y\nn\nn\n");
+    assert!(
+        output.status.success(),
+        "cresca approve --patch should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Unselected hunks should be discarded"
+    );
+
+    let output = repo.git(&["show", "HEAD:feature.txt"]);
+    let approved = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        approved.contains("first line changed"),
+        "Selected hunk should be approved, got: {}",
+        approved
+    );
+    assert!(
+        approved.contains("line 10"),
+        "Unselected hunk should not be approved, got: {}",
+        approved
+    );
+    let output = repo.git(&["ls-files"]);
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("new.txt"),
+        "Unselected new file should not be approved"
+    );
+
+    let output = repo.run_cresca(&["status", "--short"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("develop: 2f"),
+        "The unselected changes should remain unreviewed, got: {}",
+        stdout
+    );
+}
+
+/// Test that `cresca export` writes the approved changes as a patch
+#[test]
+fn test_export() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("reviewed.txt", "reviewed content");
+    repo.write_file("not_reviewed.txt", "not reviewed content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["export"]);
+    assert!(
+        !output.status.success(),
+        "cresca export should fail without approved changes"
+    );
+
+    repo.git(&["add", "reviewed.txt"]);
+    repo.run_cresca(&["approve", "-m", "Approve reviewed.txt"]);
+
+    let output = repo.run_cresca(&["export", "--output", "review.patch"]);
+    assert!(
+        output.status.success(),
+        "cresca export should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let patch = std::fs::read_to_string(repo.path().join("review.patch"))
+        .expect("Failed to read the patch");
+    assert!(
+        patch.contains("Subject: [PATCH] Approve reviewed.txt"),
+        "Patch should contain the approval commit, got: {}",
+        patch
+    );
+    assert!(
+        patch.contains("+reviewed content") && !patch.contains("not_reviewed.txt"),
+        "Patch should contain only the approved changes, got: {}",
+        patch
+    );
+
+    let output = repo.run_cresca(&["export", "-o", "-"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        patch,
+        "`-` should write the same patch to stdout"
+    );
+}
+
+/// Test that `cresca review --preview` lists the commits trimmed by `--skip-to` and `--stop-at` without modifying anything
+#[test]
+fn test_review_preview() {
+    let repo = TempGitRepo::new();
+
+    // Create develop branch with multiple commits
+    repo.create_branch("develop");
+    for i in 1..=4 {
+        repo.write_file(&format!("file{}.txt", i), &format!("content {}", i));
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+
+    let log_output = repo.git(&["log", "--format=%h", "main..develop"]);
+    let log_str = String::from_utf8_lossy(&log_output.stdout);
+    let commits: Vec<&str> = log_str.lines().collect();
+    // commits[0] = file4, commits[1] = file3, commits[2] = file2, commits[3] = file1
+
+    repo.switch_branch("main");
+    // The working directory does not need to be clean
+    repo.write_file("scratch.txt", "scratch");
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--preview",
+        "--skip-to",
+        commits[2],
+        "--stop-at",
+        commits[1],
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --preview should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for (action, message) in [
+        ("exclude", "Add file4"),
+        ("review", "Add file3"),
+        ("review", "Add file2"),
+        ("auto-approve", "Add file1"),
+    ] {
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line.trim_start().starts_with(action) && line.ends_with(message)),
+            "{} should be marked as {}, got: {}",
+            message,
+            action,
+            stdout
+        );
+    }
+    assert!(
+        stdout.contains("2 to review, 1 auto-approved, 1 excluded."),
+        "Should print the counts, got: {}",
+        stdout
+    );
+
+    assert_eq!(repo.current_branch(), "main", "Should stay on main");
+    let output = repo.git(&["branch", "--list", "review-*"]);
+    assert!(
+        output.stdout.is_empty(),
+        "No review branch should be created"
+    );
+}
+
+/// Test that `cresca review` accepts a tag as the target without switching to or pulling it
+#[test]
+fn test_review_against_tag() {
+    let repo = TempGitRepo::new();
+
+    repo.git(&["tag", "v1.0"]);
+    repo.write_file("release.txt", "after release");
+    repo.git(&["add", "."]);
+    repo.commit("Change main after the release");
+    repo.git(&["push", "origin", "main"]);
+
+    repo.git(&["switch", "--quiet", "-c", "develop", "v1.0"]);
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "v1.0", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review v1.0 develop should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "review-v1.0-develop");
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(status["from_branch"], "develop");
+    assert_eq!(status["files"], serde_json::json!(["feature.txt"]));
+
+    // The review branch against a tag is not treated as stale
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["clean"]);
+    assert!(output.status.success(), "cresca clean should succeed");
+    let output = repo.git(&["branch", "--list", "review-v1.0-develop"]);
+    assert!(
+        !output.stdout.is_empty(),
+        "The review branch should not be deleted"
+    );
+}
+
+/// Test that `cresca review --range` reviews exactly the changes between two commits
+#[test]
+fn test_review_range() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for i in 1..=3 {
+        repo.write_file(&format!("file{}.txt", i), &format!("content {}", i));
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+    // Not pushed: the range is used as it is without pulling
+
+    let output = repo.run_cresca(&["review", "--range", "develop~2..develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review --range should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        repo.current_branch().starts_with("review-"),
+        "Should be on a review branch, got: {}",
+        repo.current_branch()
+    );
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["file2.txt", "file3.txt"]),
+        "Only the changes in the range should be reviewed"
+    );
+
+    repo.run_cresca(&["abort"]);
+    for range in ["develop", "develop...main", "..develop", "missing..develop"] {
+        let output = repo.run_cresca(&["review", "--range", range]);
+        assert!(
+            !output.status.success(),
+            "cresca review --range {} should fail",
+            range
+        );
+    }
+}
+
+/// Test that `cresca review --autostash` stashes uncommitted changes and keeps the stash
+#[test]
+fn test_review_autostash() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    repo.write_file("README.md", "work in progress");
+    repo.write_file("notes.txt", "untracked notes");
+
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail with uncommitted changes"
+    );
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--autostash"]);
+    assert!(
+        output.status.success(),
+        "cresca review --autostash should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("git stash pop"),
+        "Should tell how to restore the changes, got: {}",
+        stdout
+    );
+    let status = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&status.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["feature.txt"]),
+        "Stashed changes should not be part of the review"
+    );
+
+    repo.run_cresca(&["abort"]);
+    repo.git(&["stash", "pop"]);
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+        "work in progress"
+    );
+    assert!(
+        repo.path().join("notes.txt").exists(),
+        "Untracked files should be restored"
+    );
+}
+
+/// Test that repeated `-v` increases the verbosity and `--verbose` is the same as `-vv`
+#[test]
+fn test_verbosity_levels() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature content");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let run = |args: &[&str]| {
+        let output = repo.run_cresca(args);
+        assert!(
+            output.status.success(),
+            "cresca {:?} should succeed\nstdout: {}\nstderr: {}",
+            args,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let stdout = run(&["status", "--short"]);
+    assert!(!stdout.contains("[git "), "Should not print git commands");
+
+    // `git rev-parse --is-inside-work-tree` prints `true`
+    let stdout = run(&["status", "--short", "-v"]);
+    assert!(stdout.contains("[git "), "-v should print git commands");
+    assert!(
+        !stdout.lines().any(|line| line == "true"),
+        "-v should not print git output, got: {}",
+        stdout
+    );
+
+    for args in [
+        ["status", "--short", "-vv"],
+        ["status", "--short", "--verbose"],
+    ] {
+        let stdout = run(&args);
+        assert!(
+            stdout.lines().any(|line| line == "true"),
+            "{:?} should print git output, got: {}",
+            args,
+            stdout
+        );
+        assert!(!stdout.contains("[took "), "Should not print durations");
+    }
+
+    let stdout = run(&["status", "--short", "-vvv"]);
+    assert!(stdout.contains("[took "), "-vvv should print durations");
+}
+
+/// Test that the auto-approve commit message summarizes the auto-approved commits and records the reviewer
+#[test]
+fn test_auto_approve_commit_message() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for i in 1..=3 {
+        repo.write_file(&format!("file{}.txt", i), &format!("content {}", i));
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    let output = repo.git(&["rev-parse", "develop"]);
+    let skip_to = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", &skip_to]);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.git(&["log", "-1", "--format=%B"]);
+    let message = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        message.starts_with(&format!("Auto-approve 2 commits up to {}", &skip_to[..7])),
+        "Message should summarize the auto-approved commits, got: {}",
+        message
+    );
+    assert!(
+        message.contains("Auto-approved-by: Test User"),
+        "Message should record the reviewer, got: {}",
+        message
+    );
+
+    // A single auto-approved commit is not pluralized
+    let output = repo.git(&["rev-parse", "develop^"]);
+    let skip_to = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    repo.git(&["stash", "--include-untracked"]);
+    repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        &skip_to,
+        "--force",
+        "--yes",
+    ]);
+    let output = repo.git(&["log", "-1", "--format=%B"]);
+    let message = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        message.starts_with(&format!("Auto-approve 1 commit up to {}", &skip_to[..7])),
+        "Message should summarize the auto-approved commit, got: {}",
+        message
+    );
+}
+
+/// Test that `--no-verify` bypasses a failing pre-commit hook for cresca's commits.
+#[test]
+#[cfg(unix)]
+fn test_no_verify_bypasses_hooks() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for i in 1..=2 {
+        repo.write_file(&format!("file{}.txt", i), &format!("content {}", i));
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    let output = repo.git(&["rev-parse", "develop"]);
+    let skip_to = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    repo.switch_branch("main");
+
+    let path = repo.write_fake_command("pre-commit", "#!/bin/sh\necho 'hook failed' >&2\nexit 1\n");
+    let hooks_dir = path.split(':').next().unwrap();
+    repo.git(&["config", "core.hooksPath", hooks_dir]);
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", &skip_to]);
+    assert!(
+        !output.status.success(),
+        "cresca review --skip-to should fail on the pre-commit hook"
+    );
+    repo.run_cresca(&["abort"]);
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        &skip_to,
+        "--no-verify",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --no-verify should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    repo.git(&["add", "."]);
+    let output = repo.run_cresca(&["approve", "-y"]);
+    assert!(
+        !output.status.success(),
+        "cresca approve should fail on the pre-commit hook"
+    );
+    let output = repo.run_cresca(&["approve", "-y", "--no-verify"]);
+    assert!(
+        output.status.success(),
+        "cresca approve --no-verify should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let diff = repo.git(&["diff", "HEAD", "develop"]);
+    assert!(
+        diff.stdout.is_empty(),
+        "All changes should be approved, got: {}",
+        String::from_utf8_lossy(&diff.stdout)
+    );
+}
+
+/// Test that cresca signs the approve commits when `commit.gpgsign` is set.
+#[test]
+#[cfg(unix)]
+fn test_sign_commits() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    for i in 1..=2 {
+        repo.write_file(&format!("file{}.txt", i), &format!("content {}", i));
+        repo.git(&["add", "."]);
+        repo.commit(&format!("Add file{}", i));
+    }
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    let output = repo.git(&["rev-parse", "develop"]);
+    let skip_to = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    repo.switch_branch("main");
+
+    let path = repo.write_fake_command(
+        "fake-gpg",
+        "#!/bin/sh\ncat >/dev/null\necho '-----BEGIN PGP SIGNATURE-----'\necho 'fake'\necho '-----END PGP SIGNATURE-----'\nprintf '\\n[GNUPG:] SIG_CREATED D 1 8 00 0 X\\n' >&2\n",
+    );
+    repo.git(&["config", "gpg.program", "fake-gpg"]);
+    repo.git(&["config", "commit.gpgsign", "true"]);
+
+    let output = repo.run_cresca_with_env(
+        &["-v", "review", "main", "develop", "--skip-to", &skip_to],
+        &[("PATH", &path)],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("commit --quiet -S"),
+        "The auto-approve commit should be signed, got: {}",
+        stdout
+    );
+
+    repo.git(&["add", "."]);
+    let output = repo.run_cresca_with_env(&["-v", "approve"], &[("PATH", &path)]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("commit --quiet -S"),
+        "The approve commit should be signed, got: {}",
+        stdout
+    );
+    let output = repo.git(&["cat-file", "commit", "HEAD"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("gpgsig"),
+        "The approve commit should have a signature"
+    );
+
+    // Signing failures are reported
+    repo.git(&["reset", "--soft", "HEAD~1"]);
+    repo.git(&["config", "gpg.program", "false"]);
+    let output = repo.run_cresca(&["approve"]);
+    assert!(
+        !output.status.success(),
+        "cresca approve should fail when signing fails"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Signing the commit failed"),
+        "Should show error about signing, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca status --branch` shows the status of another review branch.
+#[test]
+fn test_status_other_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "feature1.txt"]);
+    repo.run_cresca(&["approve", "-y"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&[
+        "status",
+        "--short",
+        "--no-color",
+        "--branch",
+        "review-main-develop",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca status --branch should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "develop: 1f +1 -0\n"
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    let output = repo.run_cresca(&["status", "--branch", "develop"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --branch should fail for a non-review branch"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("develop is not a review branch"),
+        "Should show error about the branch, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca status --per-file` shows the line counts of each file.
+#[test]
+fn test_status_per_file() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("small.txt", "line 1\n");
+    repo.write_file("large.txt", "line 1\nline 2\nline 3\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--per-file", "--no-color"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca status --per-file should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("- large.txt  +3/-0") && stdout.contains("- small.txt  +1/-0"),
+        "Status should show the line counts of each file, got: {}",
+        stdout
+    );
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(
+        json["file_stats"][0],
+        serde_json::json!({"path": "large.txt", "insertions": 3, "deletions": 0, "binary": false})
+    );
+}
+
+/// Test that `cresca status --sort` orders the remaining files.
+#[test]
+fn test_status_sort() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("a.txt", "line 1\n");
+    repo.write_file("b.txt", "line 1\nline 2\nline 3\n");
+    repo.write_file("c.txt", "line 1\nline 2\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add files");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let files = |args: &[&str]| {
+        let output = repo.run_cresca(args);
+        assert!(
+            output.status.success(),
+            "cresca status should succeed\nstdout: {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+        json["files"].clone()
+    };
+    assert_eq!(
+        files(&["status", "--json"]),
+        serde_json::json!(["a.txt", "b.txt", "c.txt"])
+    );
+    assert_eq!(
+        files(&["status", "--json", "--sort", "size"]),
+        serde_json::json!(["b.txt", "c.txt", "a.txt"])
+    );
+}
+
+/// Test that `cresca review` warns when the merge base moved since the review branch was created.
+#[test]
+fn test_review_warns_moved_merge_base() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        !String::from_utf8_lossy(&output.stderr).contains("warning"),
+        "Should not warn before the merge base moves"
+    );
+
+    repo.git(&["add", "."]);
+    repo.run_cresca(&["approve", "-y"]);
+
+    // Merge a new commit of main into develop
+    repo.switch_branch("main");
+    repo.write_file("main.txt", "main change");
+    repo.git(&["add", "."]);
+    repo.commit("Change main");
+    repo.switch_branch("develop");
+    repo.git(&["merge", "--quiet", "--no-edit", "main"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("warning")
+            && stderr.contains("merge base of main and develop has moved")
+            && stderr.contains("main gained commits")
+            && stderr.contains("cresca review --force"),
+        "Should warn about the moved merge base, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca review --force` recreates the review branch and discards prior approvals.
+#[test]
+fn test_review_force() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "feature1.txt"]);
+    repo.run_cresca(&["approve", "-y"]);
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--force", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca review --force should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "review-main-develop");
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(
+        json["files"],
+        serde_json::json!(["feature1.txt", "feature2.txt"]),
+        "Approved files should be unreviewed again"
+    );
+}
+
+/// Test that `cresca review --worktree` prepares the review branch in a worktree.
+#[test]
+fn test_review_worktree() {
+    let repo = TempGitRepo::new();
+    let worktree_dir = tempfile::TempDir::new().expect("Failed to create worktree temp directory");
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    repo.write_global_config(&format!(
+        "worktree_dir = \"{}\"\n",
+        worktree_dir.path().display()
+    ));
+    // Uncommitted changes in the current checkout do not matter
+    repo.write_file("scratch.txt", "work in progress");
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--worktree"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca review --worktree should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let worktree = worktree_dir.path().join("review-main-develop");
+    assert!(
+        stdout.contains(&worktree.display().to_string()),
+        "Should print the worktree path, got: {}",
+        stdout
+    );
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        repo.path().join("scratch.txt").exists(),
+        "The current checkout should be kept"
+    );
+
+    let output = repo.run_cresca_in(&worktree, &["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["files"], serde_json::json!(["feature.txt"]));
+
+    let output = repo.run_cresca_in(&worktree, &["approve", "--all", "-y"]);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed in the worktree\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = repo.git(&["log", "-1", "--format=%s", "review-main-develop"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Approve reviewed changes"
+    );
+
+    // Reviewing again reuses the worktree
+    let output = repo.run_cresca(&["review", "main", "develop", "--worktree"]);
+    assert!(
+        output.status.success(),
+        "cresca review --worktree should succeed again\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that `cresca status --format` renders the template.
+#[test]
+fn test_status_format() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&[
+        "status",
+        "--format",
+        "{from_branch}: {file_count} files, {insertions}+/{deletions}- {{done}}",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca status --format should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "develop: 2 files, 2+/0- {done}\n"
+    );
+
+    let output = repo.run_cresca(&["status", "--format", "{unknown}"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --format should fail with an unknown placeholder"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unknown placeholder `{unknown}`"),
+        "Should show error about the placeholder, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca review` reports unrelated histories, and reviews them from an empty tree with `--allow-unrelated`.
+#[test]
+fn test_review_unrelated_histories() {
+    let repo = TempGitRepo::new();
+
+    repo.git(&["checkout", "--orphan", "other"]);
+    repo.git(&["rm", "-rf", "--quiet", "."]);
+    repo.write_file("other.txt", "unrelated content");
+    repo.git(&["add", "."]);
+    repo.commit("Start unrelated history");
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "other", "--no-pull"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail for unrelated histories"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unrelated histories") && stderr.contains("--allow-unrelated"),
+        "Should show error about unrelated histories, got: {}",
+        stderr
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    let output = repo.run_cresca(&["review", "main", "other", "--no-pull", "--allow-unrelated"]);
+    assert!(
+        output.status.success(),
+        "cresca review --allow-unrelated should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "review-main-other");
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["files"], serde_json::json!(["other.txt"]));
+    assert_eq!(json["total_insertions"], 1);
+
+    // Reviewing again keeps the same empty base
+    repo.run_cresca(&["approve", "-y"]);
+    let output = repo.run_cresca(&["review", "main", "other", "--no-pull", "--allow-unrelated"]);
+    assert!(
+        output.status.success() && !String::from_utf8_lossy(&output.stderr).contains("warning"),
+        "cresca review --allow-unrelated should succeed again without warnings\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that `cresca status` measures the review from the stored merge base after the target branch moved.
+#[test]
+fn test_status_total_after_target_branch_moved() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "feature\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    repo.git(&["stash", "--include-untracked"]);
+
+    // main gains a commit that develop merges, which moves their merge base
+    repo.switch_branch("main");
+    repo.write_file("main.txt", "main\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add main");
+    repo.switch_branch("develop");
+    repo.git(&["merge", "--no-ff", "--quiet", "-m", "Merge main", "main"]);
+    repo.switch_branch("review-main-develop");
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("status --json should print JSON");
+    assert_eq!(json["insertions"], 2);
+    assert_eq!(
+        json["total_insertions"], 2,
+        "The total should count from the base of the review branch, got: {}",
+        json
+    );
+    assert_eq!(json["reviewed_percent"], 0);
+}
+
+/// Test that `cresca log`, `cresca export` and `cresca undo` work on a review of unrelated histories.
+#[test]
+fn test_unrelated_histories_log_export_undo() {
+    let repo = TempGitRepo::new();
+
+    repo.git(&["checkout", "--orphan", "other"]);
+    repo.git(&["rm", "-rf", "--quiet", "."]);
+    repo.write_file("other.txt", "unrelated content");
+    repo.write_file("later.txt", "reviewed later");
+    repo.git(&["add", "."]);
+    repo.commit("Start unrelated history");
+    repo.switch_branch("main");
+
+    repo.run_cresca(&["review", "main", "other", "--no-pull", "--allow-unrelated"]);
+    repo.git(&["add", "other.txt"]);
+    repo.run_cresca(&["approve"]);
+
+    let output = repo.run_cresca(&["log"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca log should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Approve"),
+        "The approve commit should be logged, got: {}",
+        stdout
+    );
+
+    let output = repo.run_cresca(&["export"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca export should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("other.txt"),
+        "The approved file should be exported, got: {}",
+        stdout
+    );
+
+    let output = repo.run_cresca(&["undo"]);
+    assert!(
+        output.status.success(),
+        "cresca undo should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    assert!(
+        String::from_utf8_lossy(&status.stdout).contains("other.txt"),
+        "The undone changes should be back in the working tree"
+    );
+}
+
+/// Test that `cresca review` does not create a review branch for an already merged development branch.
+#[test]
+fn test_review_already_merged() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.switch_branch("main");
+    repo.git(&[
+        "merge",
+        "--no-ff",
+        "--quiet",
+        "-m",
+        "Merge develop",
+        "develop",
+    ]);
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed for a merged branch\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("develop is already merged into main"),
+        "Should tell that the branch is already merged, got: {}",
+        stdout
+    );
+    assert_eq!(repo.current_branch(), "main");
+    let output = repo.git(&["branch", "--list", "review-*"]);
+    assert!(
+        output.stdout.is_empty(),
+        "No review branch should be created"
+    );
+}
+
+/// Test that `cresca finalize` merges the approved changes into the target branch.
+#[test]
+fn test_finalize() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "feature1.txt"]);
+    repo.run_cresca(&["approve", "-y"]);
+
+    let output = repo.run_cresca(&["finalize"]);
+    assert!(
+        !output.status.success(),
+        "cresca finalize should fail with unreviewed changes"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 file(s) still have unreviewed changes"),
+        "Should show error about unreviewed changes, got: {}",
+        stderr
+    );
+
+    let output = repo.run_cresca(&["finalize", "--partial", "--delete"]);
+    assert!(
+        output.status.success(),
+        "cresca finalize --partial should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        repo.path().join("feature1.txt").exists(),
+        "Approved file should be merged into main"
+    );
+    assert!(
+        !repo.path().join("feature2.txt").exists(),
+        "Unreviewed file should not be merged into main"
+    );
+    let output = repo.git(&["branch", "--list", "review-*"]);
+    assert!(
+        output.stdout.is_empty(),
+        "The review branch should be deleted"
+    );
+}
+
+/// Test that `cresca finalize` only requires the files the review is limited to to be reviewed.
+#[test]
+fn test_finalize_excluded_files() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature1.txt", "new feature 1");
+    repo.write_file("feature2.txt", "new feature 2");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop", "--exclude", "feature2.txt"]);
+    repo.git(&["add", "feature1.txt"]);
+    repo.run_cresca(&["approve", "-y"]);
+
+    let output = repo.run_cresca(&["finalize"]);
+    assert!(
+        output.status.success(),
+        "cresca finalize should succeed without --partial\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        repo.path().join("feature1.txt").exists(),
+        "Approved file should be merged into main"
+    );
+    assert!(
+        !repo.path().join("feature2.txt").exists(),
+        "Excluded file should not be merged into main"
+    );
+}
+
+/// Test that `cresca doctor` prints a checklist of the repository state.
+#[test]
+fn test_doctor() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["doctor", "--no-color"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "cresca doctor should succeed\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for line in [
+        "✓ git version",
+        "✓ Inside a git work tree",
+        "✓ Current branch: review-main-develop",
+        "✓ On a review branch of develop (target: main)",
+        "✗ Working tree has uncommitted changes",
+        "✓ Remote origin exists",
+        "✓ No merge in progress",
+    ] {
+        assert!(
+            stdout.contains(line),
+            "Doctor should report `{}`, got: {}",
+            line,
+            stdout
+        );
+    }
+
+    // Works outside a repository too
+    let dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+    let output = repo.run_cresca_in(dir.path(), &["doctor", "--no-color"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success() && stdout.contains("✗ Not inside a git work tree"),
+        "cresca doctor should report a missing repository\nstdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that `cresca config` reads and writes the cresca settings in the git config.
+#[test]
+fn test_config_command() {
+    let repo = TempGitRepo::new();
+
+    let output = repo.run_cresca(&["config", "set", "prefix", "rv"]);
+    assert!(
+        output.status.success(),
+        "cresca config set should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = repo.run_cresca(&["config", "get", "cresca.prefix"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "rv\n");
+    let output = repo.run_cresca(&["config", "list"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "cresca.prefix=rv\n"
+    );
+
+    let output = repo.run_cresca(&["config", "get", "remote"]);
+    assert!(
+        !output.status.success() && output.stdout.is_empty() && output.stderr.is_empty(),
+        "cresca config get should fail silently for an unset key\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.run_cresca(&["config", "set", "color", "never"]);
+    assert!(
+        !output.status.success(),
+        "cresca config set should fail for an unknown key"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Unknown config key `color`") && stderr.contains("cresca.remote"),
+        "Should list the known keys, got: {}",
+        stderr
+    );
+
+    // The setting is used by the commands
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop", "--no-pull"]);
+    assert_eq!(repo.current_branch(), "rv-main-develop");
+}
+
+/// Test that CRESCA_LANG selects the message language, falling back to English
+#[test]
+fn test_message_language() {
+    let repo = TempGitRepo::new();
+
+    // Japanese has a translation of this message
+    let output = repo.run_cresca_with_env(&["list"], &[("CRESCA_LANG", "ja_JP.UTF-8")]);
+    assert!(
+        output.status.success(),
+        "cresca list should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "レビューブランチが見つかりません。"
+    );
+
+    // Errors are translated too
+    let output = repo.run_cresca_with_env(&["diff"], &[("CRESCA_LANG", "ja")]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("`cresca review` を実行"));
+
+    // Messages missing from the Japanese catalog are printed in English
+    let output = repo.run_cresca_with_env(&["config", "get", "nope"], &[("CRESCA_LANG", "ja")]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown config key `nope`"));
+
+    // Unknown languages fall back to English
+    let output = repo.run_cresca_with_env(&["undo"], &[("CRESCA_LANG", "fr")]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Not on a review branch"));
+}
+
+/// Test that the output of `cresca status --json` deserializes into the library's `ReviewStatus`
+#[test]
+fn test_status_json_deserializes_into_review_status() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.rs", "fn feature() {}\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: cresca::ReviewStatus =
+        serde_json::from_slice(&output.stdout).expect("status --json should match ReviewStatus");
+    assert_eq!(status.from_branch, "develop");
+    assert_eq!(status.files, ["feature.rs"]);
+    assert_eq!(status.file_stats[0].insertions, 1);
+    assert_eq!(status.reviewed_percent, 0);
+}
+
+/// Test that a `--skip-to` commit outside of the review is rejected before anything is modified,
+/// using a mock git runner instead of a repository
+#[cfg(not(feature = "libgit2"))]
+#[test]
+fn test_skip_to_outside_range_with_mock_git_runner() {
+    use cresca::{prepare_review_branch, CrescaError, ReviewOptions};
+
+    let main = "a".repeat(40);
+    let develop = "b".repeat(40);
+    let merge_base = "c".repeat(40);
+    let commits = format!("{}\n{}\n", develop, "d".repeat(40));
+    let runner = common::MockGitRunner::new(&[
+        (
+            &["rev-parse", "--verify", "--quiet", "main^{commit}"],
+            Some(&main),
+        ),
+        (
+            &["rev-parse", "--verify", "--quiet", "develop^{commit}"],
+            Some(&develop),
+        ),
+        (&["merge-base", "--is-ancestor"], None),
+        (&["merge-base", "main", "develop"], Some(&merge_base)),
+        (&["rev-list"], Some(&commits)),
+    ]);
+
+    let result = prepare_review_branch(
+        &runner,
+        "main",
+        "develop",
+        &ReviewOptions {
+            skip_to: Some("eeeeeee"),
+            remote: "origin",
+            no_pull: true,
+            prefix: "review",
+            ..Default::default()
+        },
+        0,
+    );
+
+    assert!(
+        matches!(result, Err(CrescaError::CommitNotInRange { ref commit, .. }) if commit == "eeeeeee"),
+        "Should reject the commit outside of the review, got: {:?}",
+        result
+    );
+    let calls = runner.calls.lock().unwrap();
+    assert_eq!(
+        calls.last().map(String::as_str),
+        Some(format!("rev-list {}..develop", merge_base).as_str()),
+        "The commit range should be listed last, got: {:?}",
+        calls
+    );
+    assert!(
+        !calls
+            .iter()
+            .any(|call| ["switch", "merge ", "commit", "branch"]
+                .iter()
+                .any(|command| call.starts_with(command))),
+        "Nothing should be modified, got: {:?}",
+        calls
+    );
+}
+
+/// Test that `cresca stats` summarizes every review branch without switching branches
+#[test]
+fn test_stats_of_review_branches() {
+    let repo = TempGitRepo::new();
+
+    for branch in ["develop", "feature"] {
+        repo.switch_branch("main");
+        repo.create_branch(branch);
+        repo.write_file(&format!("{}1.txt", branch), "line\n");
+        repo.write_file(&format!("{}2.txt", branch), "line\n");
+        repo.git(&["add", "."]);
+        repo.commit("Add feature");
+        repo.git(&["push", "-u", "origin", branch]);
+    }
+
+    // Approve half of develop, then leave feature unreviewed
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "develop1.txt"]);
+    repo.run_cresca(&["approve"]);
+    repo.run_cresca(&["review", "main", "feature"]);
+    repo.git(&["stash", "--include-untracked"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["stats"]);
+    assert!(
+        output.status.success(),
+        "cresca stats should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines.len(),
+        3,
+        "Should print a header and two rows, got: {}",
+        stdout
+    );
+    assert!(lines[0].starts_with("BRANCH") && lines[0].ends_with("REVIEWED"));
+    let develop: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(
+        develop,
+        ["review-main-develop", "develop", "1", "+1/-0", "50%"]
+    );
+    let feature: Vec<&str> = lines[2].split_whitespace().collect();
+    assert_eq!(
+        feature,
+        ["review-main-feature", "feature", "2", "+2/-0", "0%"]
+    );
+    assert_eq!(repo.current_branch(), "main");
+}
+
+/// Test that `cresca stats` aligns the columns by display width for branch names with wide characters
+#[test]
+fn test_stats_aligns_wide_branch_names() {
+    use unicode_width::UnicodeWidthStr;
+
+    let repo = TempGitRepo::new();
+
+    for branch in ["develop", "機能"] {
+        repo.switch_branch("main");
+        repo.create_branch(branch);
+        repo.write_file(&format!("{}.txt", branch), "line\n");
+        repo.git(&["add", "."]);
+        repo.commit("Add feature");
+        repo.git(&["push", "-u", "origin", branch]);
+        repo.switch_branch("main");
+        repo.run_cresca(&["review", "main", branch]);
+        repo.git(&["stash", "--include-untracked"]);
+    }
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["stats"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines.len(),
+        3,
+        "Should print a header and two rows, got: {}",
+        stdout
+    );
+    for line in &lines {
+        assert_eq!(
+            line.width(),
+            lines[0].width(),
+            "Every row should be as wide as the header, got:\n{}",
+            stdout
+        );
+    }
+    let from_column = |line: &str, from: &str| line[..line.find(from).unwrap()].width();
+    assert_eq!(
+        from_column(lines[1], " develop "),
+        from_column(lines[2], " 機能 "),
+        "The development branches should start in the same column, got:\n{}",
+        stdout
+    );
+}
+
+/// Test that `cresca review --skip-to` rejects a merge commit, and that skipping past it works
+#[test]
+fn test_review_skip_to_merge_commit() {
+    let repo = TempGitRepo::new();
+
+    // develop: A, then a merge of side (S), then B
+    repo.create_branch("develop");
+    repo.write_file("a.txt", "a");
+    repo.git(&["add", "."]);
+    repo.commit("Add a");
+    repo.create_branch("side");
+    repo.write_file("side.txt", "side");
+    repo.git(&["add", "."]);
+    repo.commit("Add side");
+    repo.switch_branch("develop");
+    repo.git(&["merge", "--no-ff", "--quiet", "-m", "Merge side", "side"]);
+    let merge = repo.git(&["rev-parse", "HEAD"]);
+    let merge = String::from_utf8_lossy(&merge.stdout).trim().to_string();
+    repo.write_file("b.txt", "b");
+    repo.git(&["add", "."]);
+    repo.commit("Add b");
+    let after_merge = repo.git(&["rev-parse", "HEAD"]);
+    let after_merge = String::from_utf8_lossy(&after_merge.stdout)
+        .trim()
+        .to_string();
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", &merge[..7]]);
+    assert!(
+        !output.status.success(),
+        "Skipping to a merge commit should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("is a merge commit"),
+        "Should explain that the commit is a merge commit, got: {}",
+        stderr
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    // Skipping to the commit after the merge auto-approves the merge as a whole
+    let output = repo.run_cresca(&["review", "main", "develop", "--skip-to", &after_merge]);
+    assert!(
+        output.status.success(),
+        "cresca review --skip-to should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_str = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_str.contains("a.txt")
+            && files_str.contains("side.txt")
+            && !files_str.contains("b.txt"),
+        "The merge and the commits before it should be auto-approved, got: {}",
+        files_str
+    );
+}
+
+/// Test that `cresca review --no-squash` reviews the commits one by one and keeps their messages
+#[test]
+fn test_review_no_squash() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("a.txt", "a");
+    repo.git(&["add", "."]);
+    repo.commit("Add a");
+    repo.write_file("b.txt", "b");
+    repo.git(&["add", "."]);
+    repo.commit("Add b");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--no-squash"]);
+    assert!(
+        output.status.success(),
+        "cresca review --no-squash should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // Only the first commit is under review
+    let untracked = repo.git(&["ls-files", "--others", "--exclude-standard"]);
+    let untracked = String::from_utf8_lossy(&untracked.stdout);
+    assert!(
+        untracked.contains("a.txt") && !untracked.contains("b.txt"),
+        "Only the changes of the first commit should be under review, got: {}",
+        untracked
+    );
+
+    let output = repo.run_cresca(&["approve", "--all", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Reviewing commit") && stdout.contains("Add b"),
+        "Should move on to the next commit, got: {}",
+        stdout
+    );
+    let untracked = repo.git(&["ls-files", "--others", "--exclude-standard"]);
+    let untracked = String::from_utf8_lossy(&untracked.stdout);
+    assert!(
+        untracked.contains("b.txt"),
+        "The changes of the second commit should be under review, got: {}",
+        untracked
+    );
+
+    let output = repo.run_cresca(&["approve", "--all", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("Reviewing commit"),
+        "There should be no commit left to review"
+    );
+
+    let log = repo.git(&["log", "--format=%s", "main..HEAD"]);
+    let log = String::from_utf8_lossy(&log.stdout);
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        vec!["Add b", "Add a"],
+        "The commit messages should be preserved"
+    );
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--no-squash",
+        "--detect-conflicts",
+    ]);
+    assert!(
+        !output.status.success(),
+        "--no-squash should conflict with --detect-conflicts"
+    );
+}
+
+/// Test that `cresca continue` resumes a no-squash review stopped on a conflicting commit
+#[test]
+fn test_continue_no_squash_after_conflicting_commit() {
+    let repo = TempGitRepo::new();
+
+    // The first commit modifies README.md and the second deletes it
+    repo.create_branch("develop");
+    repo.write_file("README.md", "# Develop");
+    repo.write_file("a.txt", "a");
+    repo.git(&["add", "."]);
+    repo.commit("Update README and add a");
+    repo.git(&["rm", "--quiet", "README.md"]);
+    repo.write_file("b.txt", "b");
+    repo.git(&["add", "."]);
+    repo.commit("Remove README and add b");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    // Excluding README.md keeps it unmodified on the review branch, so the deletion conflicts
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--no-squash",
+        "--exclude",
+        "README.md",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --no-squash should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.run_cresca(&["approve", "--all", "--yes"]);
+    assert!(
+        !output.status.success(),
+        "cresca approve should stop on the conflicting commit"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Merge conflicts") && stderr.contains("README.md"),
+        "Should list the conflicted file, got: {}",
+        stderr
+    );
+
+    // Resolve the conflict and continue
+    repo.git(&["rm", "--quiet", "README.md"]);
+    let output = repo.run_cresca(&["continue"]);
+    assert!(
+        output.status.success(),
+        "cresca continue should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // The deletion stays excluded and the rest of the commit is under review
+    let status = repo.git(&["status", "--porcelain"]);
+    let status_str = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status_str.contains("?? b.txt") && !status_str.contains("README.md"),
+        "Only b.txt should be under review, got: {}",
+        status_str
+    );
+
+    // The conflicted commit is the one under review and approving it ends the review
+    let output = repo.run_cresca(&["approve", "--all", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("Reviewing commit"),
+        "There should be no commit left to review"
+    );
+    let log = repo.git(&["log", "--format=%s", "main..HEAD"]);
+    let log = String::from_utf8_lossy(&log.stdout);
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        vec!["Remove README and add b", "Update README and add a"],
+        "The commit messages should be preserved"
+    );
+}
+
+/// Test that `cresca review` lists the reviewed commits that disappeared after a force-push
+#[test]
+fn test_review_force_pushed_from_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("a.txt", "a");
+    repo.git(&["add", "."]);
+    repo.commit("Add a");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    repo.run_cresca(&["approve", "--all", "--yes"]);
+
+    // Rebase develop onto a new commit of main and force-push it
+    repo.switch_branch("main");
+    repo.write_file("main.txt", "main change");
+    repo.git(&["add", "."]);
+    repo.commit("Change main");
+    repo.git(&["push", "origin", "main"]);
+    repo.git(&["rebase", "--quiet", "main", "develop"]);
+    repo.git(&["commit", "--amend", "--quiet", "-m", "Add a, reworded"]);
+    repo.git(&["push", "-f", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        !output.status.success(),
+        "Reviewing a force-pushed development branch should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("develop was rewritten")
+            && stderr.contains("Add a")
+            && !stderr.contains("reworded")
+            && stderr.contains("cresca review --force"),
+        "Should list the disappeared commits and suggest --force, got: {}",
+        stderr
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    let output = repo.run_cresca(&["review", "main", "develop", "--force", "--yes"]);
+    assert!(
+        output.status.success(),
+        "cresca review --force should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let untracked = repo.git(&["ls-files", "--others", "--exclude-standard"]);
+    assert!(
+        String::from_utf8_lossy(&untracked.stdout).contains("a.txt"),
+        "The rewritten commits should be under review again"
+    );
+}
+
+/// Test that `cresca review --timeout` gives up on a pull from an unresponsive remote
+#[cfg(unix)]
+#[test]
+fn test_review_pull_timeout() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    // A remote that never answers, without holding the pipes of cresca once git is killed
+    repo.git(&[
+        "config",
+        "remote.origin.uploadpack",
+        "exec sleep 5 2>/dev/null; :",
+    ]);
+
+    let start = std::time::Instant::now();
+    let output = repo.run_cresca(&["review", "main", "develop", "--timeout", "1"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail when the pull times out"
+    );
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(4),
+        "The pull should be killed after the timeout, took {:?}",
+        start.elapsed()
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Timed out trying to pull develop branch after 1 second(s)"),
+        "Should explain that the pull timed out, got: {}",
+        stderr
+    );
+}
+
+/// Test that `cresca review` draws no spinner when stderr is not a terminal, and a failing pull
+/// prints its error on a clean line
+#[test]
+fn test_review_failing_pull_without_terminal() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+    repo.git(&["remote", "set-url", "origin", "/nonexistent/remote.git"]);
+
+    // The output of cresca is captured, so stderr is a pipe
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        !output.status.success(),
+        "cresca review should fail when the pull fails"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains('\r') && !stderr.contains("\x1B[2K") && !stderr.contains("Fetching"),
+        "No spinner should be drawn, got: {:?}",
+        stderr
+    );
+    assert!(
+        stderr.starts_with("error: Failed to pull develop branch."),
+        "The error should start on a clean line, got: {:?}",
+        stderr
+    );
+}
+
+/// Test that `cresca review --retries` retries a pull that fails intermittently, with the pulls
+/// bounded by `--timeout` going through a mock git runner instead of a remote
+#[cfg(not(feature = "libgit2"))]
+#[test]
+fn test_review_retries_pull_with_mock_git_runner() {
+    use cresca::{prepare_review_branch, CrescaError, ReviewOptions};
+    use std::time::Duration;
+
+    let commit = "a".repeat(40);
+    let runner = common::MockGitRunner::new(&[
+        (&["remote"], Some("origin\n")),
+        (&["show-ref", "--verify"], Some("")),
+        (&["switch"], Some("")),
+        (&["pull"], Some("")),
+        (&["rev-parse", "--verify", "--quiet"], Some(&commit)),
+    ])
+    .failing_first(&["pull", "origin", "develop"], 2);
+
+    let result = prepare_review_branch(
+        &runner,
+        "main",
+        "develop",
+        &ReviewOptions {
+            retries: 2,
+            timeout: Some(Duration::from_secs(60)),
+            remote: "origin",
+            prefix: "review",
+            ..Default::default()
+        },
+        0,
+    );
+
+    // Both branches resolve to the same commit, so the review stops right after the pulls
+    assert!(
+        matches!(result, Err(CrescaError::SameCommit { .. })),
+        "The pulls should succeed after the retries, got: {:?}",
+        result
+    );
+    let calls = runner.calls.lock().unwrap();
+    let pulls: Vec<&String> = calls
+        .iter()
+        .filter(|call| call.starts_with("pull"))
+        .collect();
+    assert_eq!(
+        pulls,
+        vec![
+            "pull origin develop",
+            "pull origin develop",
+            "pull origin develop",
+            "pull origin main"
+        ],
+        "The failing pull should be retried twice"
+    );
+}
+
+/// Test that `--author` attributes the approve and auto-approve commits, and rejects malformed authors
+#[test]
+fn test_author_override() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("a.txt", "a");
+    repo.git(&["add", "."]);
+    repo.commit("Add a");
+    repo.write_file("b.txt", "b");
+    repo.git(&["add", "."]);
+    repo.commit("Add b");
+    let second = repo.git(&["rev-parse", "HEAD"]);
+    let second = String::from_utf8_lossy(&second.stdout).trim().to_string();
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    let output = repo.run_cresca(&["review", "main", "develop", "--author", "Reviewer"]);
+    assert!(
+        !output.status.success(),
+        "A malformed author should be rejected"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Name <email>"),
+        "Should explain the expected format, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "main");
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--skip-to",
+        &second,
+        "--author",
+        "Bot <bot@example.com>",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --author should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let author = repo.git(&["log", "-1", "--format=%an <%ae>"]);
+    assert_eq!(
+        String::from_utf8_lossy(&author.stdout).trim(),
+        "Bot <bot@example.com>",
+        "The auto-approve commit should be attributed to the given author"
+    );
+
+    let output = repo.run_cresca(&[
+        "approve",
+        "--all",
+        "--yes",
+        "--author",
+        "Jane Doe <jane@example.com>",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca approve --author should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let author = repo.git(&["log", "-1", "--format=%an <%ae>|%cn"]);
+    let author = String::from_utf8_lossy(&author.stdout);
+    let (author, committer) = author.trim().split_once('|').unwrap();
+    assert_eq!(
+        author, "Jane Doe <jane@example.com>",
+        "The approve commit should be attributed to the given author"
+    );
+    assert_ne!(
+        committer, "Jane Doe",
+        "The committer should stay the git identity"
+    );
+}
+
+/// Test that `cresca unapprove` moves a file of the most recent approval back into review
+#[test]
+fn test_unapprove_file_of_last_approval() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("first.txt", "first content");
+    repo.write_file("second.txt", "second content");
+    repo.write_file("third.txt", "third content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "first.txt"]);
+    repo.run_cresca(&["approve", "-y", "-m", "Approve first"]);
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "second.txt", "third.txt"]);
+    repo.run_cresca(&["approve", "-y", "-m", "Approve second and third"]);
+
+    // first.txt was approved in an earlier approval
+    let output = repo.run_cresca(&["unapprove", "first.txt"]);
+    assert!(
+        !output.status.success(),
+        "Unapproving a file of an earlier approval should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("not changed by the most recent approve commit")
+            && stderr.contains("first.txt"),
+        "Should list the paths that cannot be unapproved, got: {}",
+        stderr
+    );
+
+    let output = repo.run_cresca(&["unapprove", "second.txt"]);
+    assert!(
+        output.status.success(),
+        "cresca unapprove should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Unapproved second.txt"),
+        "Should print the unapproved file, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let status = repo.git(&["status", "--porcelain"]);
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert_eq!(
+        status.trim(),
+        "?? second.txt",
+        "second.txt should be an unreviewed change again"
+    );
+    let files_in_head = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    let files_in_head = String::from_utf8_lossy(&files_in_head.stdout);
+    assert!(
+        files_in_head.contains("first.txt")
+            && files_in_head.contains("third.txt")
+            && !files_in_head.contains("second.txt"),
+        "The other files should stay approved, got: {}",
+        files_in_head
+    );
+    let log = repo.git(&["log", "-1", "--format=%s"]);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Approve second and third",
+        "The approve commit should keep its message"
+    );
+
+    // Unapproving the last file of the approval drops the approve commit
+    repo.git(&["stash", "--include-untracked", "--quiet"]);
+    let output = repo.run_cresca(&["unapprove", "third.txt"]);
+    assert!(
+        output.status.success(),
+        "cresca unapprove should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = repo.git(&["log", "-1", "--format=%s"]);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Approve first",
+        "The emptied approve commit should be dropped"
+    );
+}
+
+/// Test that `cresca unapprove` runs the hooks for the rewritten approve commit unless `--no-verify`
+/// is given, and attributes it to `--author`
+#[test]
+#[cfg(unix)]
+fn test_unapprove_respects_hooks_and_author() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("first.txt", "first content");
+    repo.write_file("second.txt", "second content");
+    repo.git(&["add", "."]);
+    repo.commit("Add features");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    repo.git(&["add", "first.txt", "second.txt"]);
+    repo.run_cresca(&["approve", "-y", "-m", "Approve both"]);
+    let approval = repo.git(&["rev-parse", "HEAD"]);
+
+    let path = repo.write_fake_command("pre-commit", "#!/bin/sh\necho 'hook failed' >&2\nexit 1\n");
+    let hooks_dir = path.split(':').next().unwrap();
+    repo.git(&["config", "core.hooksPath", hooks_dir]);
+
+    let output = repo.run_cresca(&["unapprove", "second.txt"]);
+    assert!(
+        !output.status.success(),
+        "cresca unapprove should fail on the pre-commit hook"
+    );
+    assert_eq!(
+        repo.git(&["rev-parse", "HEAD"]).stdout,
+        approval.stdout,
+        "The approval should be kept as it was when the hook fails"
+    );
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "Nothing should be left unapproved when the hook fails"
+    );
+
+    let output = repo.run_cresca(&[
+        "unapprove",
+        "second.txt",
+        "--no-verify",
+        "--author",
+        "Reviewer <reviewer@example.com>",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca unapprove --no-verify should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = repo.git(&["log", "-1", "--format=%s|%an <%ae>"]);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Approve both|Reviewer <reviewer@example.com>",
+        "The rewritten approve commit should keep its message and use the given author"
+    );
+}
+
+/// Test that `cresca status --against` computes the remaining diff against the given commit
+#[test]
+fn test_status_against_commit() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("first.txt", "first");
+    repo.git(&["add", "."]);
+    repo.commit("Add first");
+    let first = repo.git(&["rev-parse", "HEAD"]);
+    let first = String::from_utf8_lossy(&first.stdout).trim().to_string();
+    repo.write_file("second.txt", "second");
+    repo.git(&["add", "."]);
+    repo.commit("Add second");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--short"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("2f"),
+        "Both files should remain against the branch tip, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = repo.run_cresca(&["status", "--json", "--against", &first[..7]]);
+    assert!(
+        output.status.success(),
+        "cresca status --against should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["first.txt"]),
+        "Only the changes up to the given commit should remain"
+    );
+
+    let output = repo.run_cresca(&["status", "--against", "no-such-commit"]);
+    assert!(
+        !output.status.success(),
+        "cresca status --against should fail for an unknown commit"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("`no-such-commit` does not resolve to a commit"),
+        "Should explain that the commit does not resolve, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Test that a change of the executable bit alone is discarded when not approved, and committed
+/// when approved, also with `core.fileMode=false`
+#[cfg(unix)]
+#[test]
+fn test_executable_bit_change() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TempGitRepo::new();
+    repo.write_file("script.sh", "echo hello");
+    repo.git(&["add", "."]);
+    repo.commit("Add script");
+    repo.git(&["push", "origin", "main"]);
+
+    repo.create_branch("develop");
+    std::fs::set_permissions(
+        repo.path().join("script.sh"),
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    repo.git(&["add", "."]);
+    repo.commit("Make script executable");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+    let status = repo.git(&["status", "--porcelain"]);
+    assert_eq!(
+        String::from_utf8_lossy(&status.stdout).trim(),
+        "M script.sh",
+        "The mode change should be an unreviewed change"
+    );
+
+    // Not approved: the bit change is discarded
+    let output = repo.run_cresca(&["approve", "-y"]);
+    assert!(
+        output.status.success(),
+        "cresca approve should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mode = std::fs::metadata(repo.path().join("script.sh"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o111, 0, "The executable bit should be discarded");
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "The working tree should be clean"
+    );
+
+    // Approved with git ignoring the executable bits of the working tree
+    repo.git(&["config", "core.fileMode", "false"]);
+    repo.run_cresca(&["review", "main", "develop"]);
+    let output = repo.run_cresca(&["add", "script.sh"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("1 file"),
+        "The mode change should be staged, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    repo.run_cresca(&["approve", "-y"]);
+    let tree = repo.git(&["ls-tree", "HEAD", "script.sh"]);
+    assert!(
+        String::from_utf8_lossy(&tree.stdout).starts_with("100755"),
+        "The executable bit should be committed, got: {}",
+        String::from_utf8_lossy(&tree.stdout)
+    );
+    let output = repo.run_cresca(&["status", "--short"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("0f"),
+        "Nothing should remain, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+/// Test that `cresca review --ignore-eol` hides files differing only in line endings in a CRLF repository
+#[test]
+fn test_review_ignore_eol() {
+    let repo = TempGitRepo::new();
+    repo.write_file("lines.txt", "first\nsecond\n");
+    repo.write_file("spaces.txt", "first\n");
+    repo.git(&["add", "."]);
+    repo.commit("Add lines");
+    repo.git(&["push", "origin", "main"]);
+
+    // develop converts lines.txt to CRLF, adds trailing spaces to spaces.txt and changes another file
+    repo.create_branch("develop");
+    repo.write_file("lines.txt", "first\r\nsecond\r\n");
+    repo.write_file("spaces.txt", "first  \n");
+    repo.write_file("feature.txt", "feature\n");
+    repo.git(&["-c", "core.autocrlf=false", "add", "."]);
+    repo.commit("Add feature with CRLF line endings");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+    repo.git(&["config", "core.autocrlf", "true"]);
+
+    let output = repo.run_cresca(&["review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["feature.txt", "lines.txt", "spaces.txt"]),
+        "The line ending change should be listed without --ignore-eol"
+    );
+    repo.run_cresca(&["approve", "-y"]);
+
+    let output = repo.run_cresca(&[
+        "review",
+        "main",
+        "develop",
+        "--force",
+        "--yes",
+        "--ignore-eol",
+    ]);
+    assert!(
+        output.status.success(),
+        "cresca review --ignore-eol should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = repo.run_cresca(&["status", "--json"]);
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["feature.txt", "spaces.txt"]),
+        "Only the line ending change should be ignored with --ignore-eol"
+    );
+    let output = repo.run_cresca(&["diff", "--no-color"]);
+    let diff = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        diff.contains("feature.txt") && !diff.contains("lines.txt"),
+        "The remaining diff should ignore the line ending change, got: {}",
+        diff
+    );
+}
+
+/// Test that filenames with non-ASCII characters and spaces are listed as written, not quoted
+#[test]
+fn test_status_with_unicode_filenames() {
+    let repo = TempGitRepo::new();
+    repo.write_file(
+        "naïve.txt",
+        "original content\nwith several lines\nto detect the rename",
+    );
+    repo.git(&["add", "."]);
+    repo.commit("Add naïve.txt");
+    repo.git(&["push", "origin", "main"]);
+
+    repo.create_branch("develop");
+    repo.write_file("café menu.txt", "espresso");
+    repo.git(&["mv", "naïve.txt", "naïve renamed.txt"]);
+    repo.git(&["add", "."]);
+    repo.commit("Add café menu.txt and rename naïve.txt");
+    repo.git(&["push", "-u", "origin", "develop"]);
+
+    repo.switch_branch("main");
+    repo.run_cresca(&["review", "main", "develop"]);
+
+    let output = repo.run_cresca(&["status", "--json"]);
+    assert!(
+        output.status.success(),
+        "cresca status should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Status should be valid JSON");
+    assert_eq!(
+        status["files"],
+        serde_json::json!(["café menu.txt", "naïve.txt -> naïve renamed.txt"]),
+        "Filenames should be decoded, got: {}",
+        status["files"]
+    );
+
+    let output = repo.run_cresca(&["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("café menu.txt") && !stdout.contains("\\303"),
+        "Filenames should be shown as UTF-8, got: {}",
+        stdout
+    );
+}
+
+/// Test that interrupting `cresca review` during the squash merge discards the merged changes and
+/// switches back to the original branch
+#[cfg(unix)]
+#[test]
+fn test_review_interrupted_restores_branch() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    // Hang right after the squash merge so the review can be interrupted with the merge applied
+    let marker = repo.path().join(".git").join("merged");
+    let path = repo.write_fake_command(
+        "slow-git",
+        &format!(
+            "#!/bin/sh\nif [ \"$1 $2\" = 'merge --squash' ]; then\n  '{}' \"$@\"\n  touch '{}'\n  sleep 10 >/dev/null 2>&1\n  exit 0\nfi\nexec '{}' \"$@\"\n",
+            TempGitRepo::git_executable().display(),
+            marker.display(),
+            TempGitRepo::git_executable().display()
+        ),
+    );
+    let child = repo.spawn_cresca_with_env(
+        &["--git-binary", "slow-git", "review", "main", "develop"],
+        &[("PATH", &path)],
+    );
+    for _ in 0..100 {
+        if marker.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(marker.exists(), "The squash merge should have been started");
+
+    // Interrupt git along with cresca, as Ctrl-C in a terminal does
+    std::process::Command::new("kill")
+        .args(["-INT", "--", &format!("-{}", child.id())])
+        .status()
+        .expect("Failed to send SIGINT");
+    let output = child.wait_with_output().expect("Failed to wait for cresca");
+    assert_eq!(
+        output.status.code(),
+        Some(130),
+        "cresca review should exit with code 130 when interrupted\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Interrupted"),
+        "Should report the interrupt, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(repo.current_branch(), "main");
+    assert!(
+        !repo.has_uncommitted_changes(),
+        "The merged changes should be discarded"
+    );
+}
+
+/// Test that `--log-file` appends the git commands of each invocation with their exit status
+#[test]
+fn test_log_file_records_git_commands() {
+    let repo = TempGitRepo::new();
+
+    repo.create_branch("develop");
+    repo.write_file("feature.txt", "new feature");
+    repo.git(&["add", "."]);
+    repo.commit("Add feature");
+    repo.git(&["push", "-u", "origin", "develop"]);
+    repo.switch_branch("main");
+
+    let log_file = repo.path().join(".git").join("cresca.log");
+    let log_path = log_file.to_string_lossy().to_string();
+    let output = repo.run_cresca(&["--log-file", &log_path, "review", "main", "develop"]);
+    assert!(
+        output.status.success(),
+        "cresca review --log-file should succeed\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = std::fs::read_to_string(&log_file).expect("The log file should be written");
+    assert!(
+        log.lines()
+            .any(|line| line.contains(" git merge --squash") && line.ends_with("(exit 0)")),
+        "The squash merge should be logged with its exit status, got: {}",
+        log
+    );
+    assert!(
+        log.lines().any(
+            |line| line.contains(" git merge-base --is-ancestor") && line.ends_with("(exit 1)")
+        ),
+        "Failed git commands should be logged with their exit status, got: {}",
+        log
+    );
+    assert!(
+        log.lines().all(|line| line.starts_with("    ")
+            || line.len() > 20 && line.as_bytes()[10] == b'T' && line[..20].ends_with('Z')),
+        "Each git command should be logged with a timestamp, got: {}",
+        log
+    );
+
+    let line_count = log.lines().count();
+    repo.run_cresca(&["--log-file", &log_path, "status"]);
+    let log = std::fs::read_to_string(&log_file).expect("The log file should be written");
+    assert!(
+        log.lines().count() > line_count,
+        "Later invocations should append to the log file, got: {}",
+        log
+    );
+}